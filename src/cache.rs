@@ -0,0 +1,382 @@
+use crate::checksum::sha256_file;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use std::{
+    fs::{self, create_dir_all, read_dir, remove_dir_all, File},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Cache size used when config.toml does not set `cache.max_size`.
+pub const DEFAULT_MAX_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Downloaded packages stored as `<cache dir>/<sha256>/<package name>`.
+///
+/// Only packages matching the checksum announced by their provider are stored, so an entry can be
+/// reused by any provider serving the same archive.
+#[derive(Debug, Clone)]
+pub struct PackageCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub checksum: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Last time the entry was stored or used
+    pub used: SystemTime,
+}
+
+impl CacheEntry {
+    pub fn package_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+impl PackageCache {
+    pub fn new<P: Into<PathBuf>>(dir: P, max_size: u64) -> Self {
+        PackageCache {
+            dir: dir.into(),
+            max_size,
+        }
+    }
+
+    /// Cached package with `checksum`. Entries whose content no longer matches are removed.
+    /// Checksums that are not a SHA-256 value are a miss, they never become a path.
+    pub fn get(&self, checksum: &str) -> Result<Option<PathBuf>> {
+        let entry_dir = match entry_name(checksum) {
+            Some(name) => self.dir.join(name),
+            None => {
+                debug!("Ignore invalid cache checksum {:?}", checksum);
+                return Ok(None);
+            }
+        };
+        let path = match package_in(&entry_dir)? {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        if sha256_file(&path)? != checksum.trim().to_lowercase() {
+            warn!("Remove corrupted cache entry {:?}", entry_dir);
+            remove_dir_all(&entry_dir)?;
+            return Ok(None);
+        }
+        touch(&path)?;
+        Ok(Some(path))
+    }
+
+    /// Move the verified package at `file` into the cache and return its new path.
+    pub fn store(&self, checksum: &str, package_name: &str, file: &Path) -> Result<PathBuf> {
+        let entry_dir = self.entry_dir(checksum)?;
+        if entry_dir.exists() {
+            remove_dir_all(&entry_dir)?;
+        }
+        create_dir_all(&entry_dir)?;
+        let path = entry_dir.join(package_name);
+        if fs::rename(file, &path).is_err() {
+            // the download directory may be on another file system
+            fs::copy(file, &path)?;
+            fs::remove_file(file)?;
+        }
+        touch(&path)?;
+        debug!("Cached {} as {:?}", package_name, path);
+
+        self.evict(checksum)?;
+        Ok(path)
+    }
+
+    /// All entries, least recently used first.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        if !self.dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut entries = vec![];
+        for entry_dir in read_dir(&self.dir)? {
+            let entry_dir = entry_dir?.path();
+            let is_entry = entry_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(entry_name)
+                .is_some();
+            if !is_entry {
+                continue;
+            }
+            let path = match package_in(&entry_dir)? {
+                Some(path) => path,
+                None => continue,
+            };
+            let metadata = fs::metadata(&path)?;
+            entries.push(CacheEntry {
+                checksum: entry_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                path,
+                size: metadata.len(),
+                used: metadata.modified()?,
+            });
+        }
+        entries.sort_by_key(|entry| entry.used);
+        Ok(entries)
+    }
+
+    /// Remove entries not used for `older_than`, or every entry, and return them.
+    pub fn prune(&self, older_than: Option<Duration>) -> Result<Vec<CacheEntry>> {
+        let now = SystemTime::now();
+        let pruned: Vec<CacheEntry> = self
+            .entries()?
+            .into_iter()
+            .filter(|entry| match older_than {
+                Some(age) => now.duration_since(entry.used).unwrap_or_default() > age,
+                None => true,
+            })
+            .collect();
+        for entry in &pruned {
+            remove_dir_all(self.entry_dir(&entry.checksum)?)?;
+        }
+        Ok(pruned)
+    }
+
+    /// Remove least recently used entries until the cache fits `max_size`, keeping `keep`.
+    fn evict(&self, keep: &str) -> Result<()> {
+        let keep = self.entry_dir(keep)?;
+        let entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+        for entry in entries {
+            if total <= self.max_size {
+                break;
+            }
+            let entry_dir = self.entry_dir(&entry.checksum)?;
+            if entry_dir == keep {
+                continue;
+            }
+            debug!("Evict {} from cache", entry.package_name());
+            remove_dir_all(entry_dir)?;
+            total -= entry.size;
+        }
+        Ok(())
+    }
+
+    fn entry_dir(&self, checksum: &str) -> Result<PathBuf> {
+        entry_name(checksum)
+            .map(|name| self.dir.join(name))
+            .ok_or_else(|| anyhow!("Invalid cache checksum {:?}", checksum))
+    }
+}
+
+/// Directory name of the entry for `checksum`. Checksums come from providers, so only SHA-256
+/// values are accepted, anything else could point outside of the cache.
+fn entry_name(checksum: &str) -> Option<String> {
+    let name = checksum.trim().to_lowercase();
+    if name.len() == 64 && name.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+fn package_in(entry_dir: &Path) -> io::Result<Option<PathBuf>> {
+    if !entry_dir.is_dir() {
+        return Ok(None);
+    }
+    for entry in read_dir(entry_dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+/// Mark a package as used so it is evicted last.
+fn touch(path: &Path) -> io::Result<()> {
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+/// Parse sizes like `500M`, `2G` or a number of bytes.
+pub fn parse_size(size: &str) -> Result<u64> {
+    let size = size.trim();
+    let (number, unit) = size.split_at(
+        size.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(size.len()),
+    );
+    let multiplier: u64 = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => return Err(anyhow!("Cannot parse size {}", size)),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow!("Cannot parse size {}", size))?;
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| anyhow!("Size {} is too large", size))
+}
+
+/// Parse durations like `30d`, `12h`, `2w` or `90m`.
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let age = age.trim();
+    let (number, unit) = age.split_at(age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len()));
+    let seconds: u64 = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(anyhow!("Cannot parse age {}. Use a value like 30d", age)),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow!("Cannot parse age {}. Use a value like 30d", age))?;
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| anyhow!("Age {} is too large", age))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_age, parse_size, PackageCache};
+    use crate::checksum::sha256_file;
+    use std::{
+        fs::{create_dir_all, write, File},
+        path::Path,
+        time::{Duration, SystemTime},
+    };
+    use tempdir::TempDir;
+
+    fn package(dir: &Path, name: &str, content: &str) -> (std::path::PathBuf, String) {
+        let path = dir.join(name);
+        write(&path, content).unwrap();
+        let checksum = sha256_file(&path).unwrap();
+        (path, checksum)
+    }
+
+    fn age(path: &Path, days: u64) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60))
+            .unwrap();
+    }
+
+    #[test]
+    fn stored_package_should_be_found_by_checksum() {
+        let dir = TempDir::new("jvc-cache").unwrap();
+        let cache = PackageCache::new(dir.path().join("cache"), 1024);
+        let (file, checksum) = package(dir.path(), "download", "jdk archive");
+
+        let cached = cache.store(&checksum, "jdk.tar.gz", &file).unwrap();
+
+        assert!(!file.exists());
+        assert_eq!(cached.file_name().unwrap(), "jdk.tar.gz");
+        assert_eq!(cache.get(&checksum.to_uppercase()).unwrap(), Some(cached));
+        assert_eq!(cache.get("0123").unwrap(), None);
+    }
+
+    #[test]
+    fn corrupted_package_should_be_removed() {
+        let dir = TempDir::new("jvc-cache").unwrap();
+        let cache = PackageCache::new(dir.path().join("cache"), 1024);
+        let (file, checksum) = package(dir.path(), "download", "jdk archive");
+        let cached = cache.store(&checksum, "jdk.tar.gz", &file).unwrap();
+
+        write(&cached, "truncated").unwrap();
+
+        assert_eq!(cache.get(&checksum).unwrap(), None);
+        assert!(cache.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_checksum_should_never_reach_outside_of_cache() {
+        let dir = TempDir::new("jvc-cache").unwrap();
+        let cache = PackageCache::new(dir.path().join("cache"), 1024);
+        let (file, checksum) = package(dir.path(), "download", "jdk archive");
+        cache.store(&checksum, "jdk.tar.gz", &file).unwrap();
+        // `..` would be the base dir and a hash mismatch would remove it
+        let (config, _) = package(dir.path(), "config.toml", "provider = \"azul\"");
+        let outside = dir.path().join("x");
+        create_dir_all(&outside).unwrap();
+        write(outside.join("jdk.tar.gz"), "jdk archive").unwrap();
+
+        assert_eq!(cache.get("..").unwrap(), None);
+        assert_eq!(cache.get("/tmp/x").unwrap(), None);
+        assert_eq!(cache.get(outside.to_str().unwrap()).unwrap(), None);
+        assert!(cache.store("..", "config.toml", &config).is_err());
+
+        assert!(config.exists());
+        assert!(outside.join("jdk.tar.gz").exists());
+        assert_eq!(cache.entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn store_should_evict_least_recently_used_above_max_size() {
+        let dir = TempDir::new("jvc-cache").unwrap();
+        let cache = PackageCache::new(dir.path().join("cache"), 25);
+        let (first, first_checksum) = package(dir.path(), "first", "0123456789");
+        let first = cache.store(&first_checksum, "first.zip", &first).unwrap();
+        age(&first, 2);
+        let (second, second_checksum) = package(dir.path(), "second", "abcdefghij");
+        cache
+            .store(&second_checksum, "second.zip", &second)
+            .unwrap();
+        cache.get(&first_checksum).unwrap();
+
+        let (third, third_checksum) = package(dir.path(), "third", "ABCDEFGHIJ");
+        cache.store(&third_checksum, "third.zip", &third).unwrap();
+
+        let names: Vec<String> = cache
+            .entries()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.package_name())
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"first.zip".to_owned()));
+        assert!(names.contains(&"third.zip".to_owned()));
+    }
+
+    #[test]
+    fn prune_should_remove_old_entries() {
+        let dir = TempDir::new("jvc-cache").unwrap();
+        let cache = PackageCache::new(dir.path().join("cache"), 1024);
+        let (old, old_checksum) = package(dir.path(), "old", "old archive");
+        age(&cache.store(&old_checksum, "old.tar.gz", &old).unwrap(), 40);
+        let (new, new_checksum) = package(dir.path(), "new", "new archive");
+        cache.store(&new_checksum, "new.tar.gz", &new).unwrap();
+
+        let pruned = cache.prune(Some(parse_age("30d").unwrap())).unwrap();
+
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].package_name(), "old.tar.gz");
+        assert_eq!(cache.entries().unwrap().len(), 1);
+        assert_eq!(cache.prune(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_size_and_age_should_accept_units() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("2K").unwrap(), 2048);
+        assert_eq!(parse_size("3G").unwrap(), 3 * 1024 * 1024 * 1024);
+        assert!(parse_size("lots").is_err());
+        assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 86400));
+        assert!(parse_age("30").is_err());
+    }
+
+    #[test]
+    fn parse_size_and_age_should_reject_overflows() {
+        assert!(parse_size("18446744073709551615G").is_err());
+        assert!(parse_size("99999999999999999999").is_err());
+        assert!(parse_age("18446744073709551615w").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use sha2::{Digest, Sha256};
+use std::{fs::File, io, path::Path};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChecksumError {
+    #[error("Checksum mismatch for {package}: expected {expected}, actual {actual}")]
+    Mismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Provider did not supply a checksum for {package}. Use --skip-checksum to install it anyway")]
+    Missing { package: String },
+}
+
+/// SHA-256 values for a downloaded package: the one announced by the provider
+/// and the one computed while the package was written to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+impl Checksum {
+    pub fn new(expected: Option<String>, hasher: Sha256) -> Self {
+        Self {
+            expected,
+            actual: to_hex(hasher),
+        }
+    }
+
+    pub fn verify(&self, package: &str) -> Result<(), ChecksumError> {
+        match &self.expected {
+            Some(expected) if expected.trim().eq_ignore_ascii_case(&self.actual) => Ok(()),
+            Some(expected) => Err(ChecksumError::Mismatch {
+                package: package.to_owned(),
+                expected: expected.trim().to_lowercase(),
+                actual: self.actual.clone(),
+            }),
+            None => Err(ChecksumError::Missing {
+                package: package.to_owned(),
+            }),
+        }
+    }
+}
+
+pub fn to_hex(hasher: Sha256) -> String {
+    format!("{:x}", hasher.finalize())
+}
+
+/// SHA-256 of the file at `path`, as lowercase hex.
+pub fn sha256_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(to_hex(hasher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha256_file, Checksum, ChecksumError};
+    use crate::test_support::create_fixture_archive;
+    use sha2::{Digest, Sha256};
+    use tempdir::TempDir;
+
+    #[test]
+    fn streamed_hash_should_match_file_hash() {
+        let dir = TempDir::new("jvc-checksum").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let bytes = std::fs::read(&archive).unwrap();
+
+        let mut hasher = Sha256::new();
+        for chunk in bytes.chunks(7) {
+            hasher.update(chunk);
+        }
+        let checksum = Checksum::new(Some(sha256_file(&archive).unwrap()), hasher);
+
+        assert!(checksum.verify("fixture-jdk.tar.gz").is_ok());
+    }
+
+    #[test]
+    fn verify_should_ignore_case_and_whitespace() {
+        let checksum = Checksum {
+            expected: Some(" ABCDEF\n".to_owned()),
+            actual: "abcdef".to_owned(),
+        };
+        assert!(checksum.verify("package").is_ok());
+    }
+
+    #[test]
+    fn verify_should_report_expected_and_actual_on_mismatch() {
+        let dir = TempDir::new("jvc-checksum").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let actual = sha256_file(&archive).unwrap();
+        let checksum = Checksum {
+            expected: Some("00".repeat(32)),
+            actual: actual.clone(),
+        };
+
+        assert_eq!(
+            checksum.verify("fixture-jdk.tar.gz"),
+            Err(ChecksumError::Mismatch {
+                package: "fixture-jdk.tar.gz".to_owned(),
+                expected: "00".repeat(32),
+                actual,
+            })
+        );
+    }
+
+    #[test]
+    fn verify_should_fail_when_checksum_is_missing() {
+        let checksum = Checksum {
+            expected: None,
+            actual: "abcdef".to_owned(),
+        };
+        assert!(matches!(
+            checksum.verify("package"),
+            Err(ChecksumError::Missing { .. })
+        ));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    config::{ClientConfig, VersionRequirements},
+    provider::Provider,
+    version::Version,
+};
+
+use super::{
+    adoptopenjdk_client::{
+        convert_adoptopenjdk_to_version, default_os, download_package_url, get_link_after_redirect,
+        get_package_name, get_total_size, page_size, select_release, AdoptOpenJDKVersion,
+        BinariesInfo, PackageInfo,
+    },
+    download::{download_client, fetch_package},
+    package_client::{DownloadResponse, PackageClient},
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::debug;
+use reqwest::Url;
+
+const ADOPTIUM_BASE_URL: &str = "https://api.adoptium.net/v3";
+const ADOPTIUM_VENDOR: &str = "eclipse";
+
+/// Client for the Eclipse Adoptium (Temurin) API, successor of the AdoptOpenJDK API.
+#[derive(Debug)]
+pub struct AdoptiumClient {
+    base_url: String,
+}
+
+impl AdoptiumClient {
+    pub fn new() -> Self {
+        AdoptiumClient::with_base_url(ADOPTIUM_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        AdoptiumClient {
+            base_url: base_url.to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl PackageClient for AdoptiumClient {
+    fn get_base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    async fn get_all_version(&self, _requirements: VersionRequirements) -> Result<Vec<Version>> {
+        let available_releases = format!("{}/info/available_releases", self.get_base_url());
+        debug!("Request all available versions: {}", available_releases);
+        let result: AdoptOpenJDKVersion = reqwest::get(&available_releases).await?.json().await?;
+
+        convert_adoptopenjdk_to_version(result, Provider::Adoptium)
+    }
+
+    async fn download(&self, client_conf: ClientConfig) -> Result<DownloadResponse> {
+        let page_size = page_size(&client_conf.version_req);
+        let binary_info_url = download_package_url(
+            client_conf.base_url.to_owned(),
+            client_conf.requirements.clone(),
+            &client_conf.version,
+            page_size,
+            ADOPTIUM_VENDOR,
+        );
+
+        let binary_info: Vec<BinariesInfo> = reqwest::get(binary_info_url).await?.json().await?;
+        let (bin, version) = select_release(&binary_info, &client_conf.version_req)?;
+        let total_size: u32 = get_total_size(bin);
+        let package_name: &str = get_package_name(bin);
+
+        let package = &bin
+            .binaries
+            .first()
+            .ok_or(anyhow!("Cannot extract package link"))?
+            .package;
+
+        let package_url = if page_size == 1 {
+            let latest_url = compose_latest_binary_url(
+                client_conf.requirements.clone(),
+                &client_conf.base_url,
+                version.feature,
+            );
+            resolve_latest_binary(&latest_url, package).await?
+        } else {
+            Url::parse(&package.link)?
+        };
+        debug!("Download package from: {}", package_url);
+
+        let (download_path, checksum) = fetch_package(
+            &client_conf,
+            &download_client()?,
+            package_url.as_str(),
+            package_name,
+            Some(package.checksum.clone()),
+            u64::from(total_size),
+        )
+        .await?;
+        Ok(DownloadResponse {
+            download_path,
+            package_name: package_name.to_owned(),
+            checksum,
+            version,
+            distribution: None,
+        })
+    }
+}
+
+/// `binary/latest` redirects to the newest package of a feature version.
+fn compose_latest_binary_url(
+    requirements: VersionRequirements,
+    api_base_url: &str,
+    feature: u32,
+) -> String {
+    format!(
+        "{}/binary/latest/{}/{}/{}/{}/{}/{}/{}/{}",
+        api_base_url,
+        feature,
+        requirements.release_type.unwrap_or("ga".to_owned()),
+        requirements.os.unwrap_or(default_os().to_owned()),
+        requirements.arch.unwrap_or("x64".to_owned()),
+        requirements.image_type.unwrap_or("jdk".to_owned()),
+        requirements.jvm_impl.unwrap_or("hotspot".to_owned()),
+        requirements.heap_size.unwrap_or("normal".to_owned()),
+        requirements.vendor.unwrap_or(ADOPTIUM_VENDOR.to_owned()),
+    )
+}
+
+/// Use the `binary/latest` redirect only when it points to the package whose checksum we know.
+async fn resolve_latest_binary(latest_url: &str, package: &PackageInfo) -> Result<Url> {
+    debug!("Resolve latest binary: {}", latest_url);
+    let location = get_link_after_redirect(latest_url).await?;
+    let file_name = location
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default();
+
+    if file_name == package.name {
+        Ok(location)
+    } else {
+        debug!(
+            "Latest binary {} differs from release {}, use release link",
+            file_name, package.name
+        );
+        Ok(Url::parse(&package.link)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdoptiumClient;
+    use crate::{
+        checksum::sha256_file,
+        client::{
+            package_client::PackageClient,
+            test_server::{Route, TestServer},
+        },
+        config::{ClientConfig, VersionRequirements},
+        provider::Provider,
+        test_support::create_fixture_archive,
+        version::{Version, VersionReq},
+    };
+    use std::fs::read;
+    use tempdir::TempDir;
+
+    const AVAILABLE_RELEASES: &str = include_str!("fixtures/adoptium/available_releases.json");
+    const FEATURE_RELEASES_17: &str = include_str!("fixtures/adoptium/feature_releases_17.json");
+    const RECORDED_CHECKSUM_17_0_1: &str =
+        "6f1335d9a7855159f982dac557420397be9aa85f3f7bc84e111d25871c02c27c";
+    const RECORDED_CHECKSUM_17_0_2: &str =
+        "288f34e3ba8a4838605636485d0365ce23e57d5f2f68997ac4c2e4c01967cd48";
+    const PACKAGE_17_0_1: &str = "/adoptium/temurin17-binaries/releases/download/jdk-17.0.1%2B12/OpenJDK17U-jdk_x64_linux_hotspot_17.0.1_12.tar.gz";
+    const PACKAGE_17_0_2: &str = "/adoptium/temurin17-binaries/releases/download/jdk-17.0.2%2B8/OpenJDK17U-jdk_x64_linux_hotspot_17.0.2_8.tar.gz";
+
+    /// Serves the recorded feature releases with package links pointing to the test server.
+    async fn adoptium_server(archive: &[u8], checksum: &str) -> TestServer {
+        let server = TestServer::start().await;
+        let releases = FEATURE_RELEASES_17
+            .replace("https://github.com", &server.base_url)
+            .replace(RECORDED_CHECKSUM_17_0_1, checksum)
+            .replace(RECORDED_CHECKSUM_17_0_2, checksum);
+        server.route("/v3/info/available_releases", Route::ok(AVAILABLE_RELEASES));
+        server.route("/v3/assets/feature_releases/17/ga", Route::ok(releases));
+        server.route(PACKAGE_17_0_1, Route::ok(archive));
+        server.route(PACKAGE_17_0_2, Route::ok(archive));
+        server.route(
+            "/v3/binary/latest/17/ga/linux/x64/jdk/hotspot/normal/eclipse",
+            Route::redirect(&format!("{}{}", server.base_url, PACKAGE_17_0_2)),
+        );
+        server
+    }
+
+    fn client_config(server: &TestServer, dir: &TempDir, version_req: &str) -> ClientConfig {
+        ClientConfig {
+            requirements: VersionRequirements {
+                os: Some("linux".to_owned()),
+                arch: Some("x64".to_owned()),
+                vendor: None,
+                ..VersionRequirements::default()
+            },
+            download_dir: dir.path().to_owned(),
+            base_url: format!("{}/v3", server.base_url),
+            version: Version::new(17, true, Provider::Adoptium),
+            version_req: version_req.parse::<VersionReq>().unwrap(),
+            cache: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_all_version_should_read_available_releases() {
+        let server = TestServer::start().await;
+        server.route("/v3/info/available_releases", Route::ok(AVAILABLE_RELEASES));
+        let client = AdoptiumClient::with_base_url(&format!("{}/v3", server.base_url));
+
+        let versions = client
+            .get_all_version(VersionRequirements::default())
+            .await
+            .unwrap();
+
+        let features: Vec<u32> = versions.iter().map(|v| v.value.feature).collect();
+        assert_eq!(features, vec![8, 11, 16, 17, 18]);
+        assert!(versions.iter().all(|v| v.provider == Provider::Adoptium));
+        assert!(versions[3].is_lts());
+        assert!(!versions[4].is_lts());
+    }
+
+    #[tokio::test]
+    async fn download_feature_version_should_use_latest_binary() {
+        let dir = TempDir::new("jvc-adoptium").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let checksum = sha256_file(&archive).unwrap();
+        let server = adoptium_server(&read(&archive).unwrap(), &checksum).await;
+        let client = AdoptiumClient::with_base_url(&format!("{}/v3", server.base_url));
+
+        let response = client
+            .download(client_config(&server, &dir, "17"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.version.to_string(), "17.0.2+8");
+        assert_eq!(
+            response.package_name,
+            "OpenJDK17U-jdk_x64_linux_hotspot_17.0.2_8.tar.gz"
+        );
+        assert!(response.checksum.verify(&response.package_name).is_ok());
+        let requests = server.requests();
+        assert!(requests[0].contains("page_size=1"));
+        assert!(requests[0].contains("vendor=eclipse"));
+        assert!(requests
+            .iter()
+            .any(|r| r.starts_with("/v3/binary/latest/17/")));
+    }
+
+    #[tokio::test]
+    async fn download_exact_version_should_pick_matching_release() {
+        let dir = TempDir::new("jvc-adoptium").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let checksum = sha256_file(&archive).unwrap();
+        let server = adoptium_server(&read(&archive).unwrap(), &checksum).await;
+        let client = AdoptiumClient::with_base_url(&format!("{}/v3", server.base_url));
+
+        let response = client
+            .download(client_config(&server, &dir, "17.0.1"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.version.to_string(), "17.0.1+12");
+        assert!(response.checksum.verify(&response.package_name).is_ok());
+        assert!(server.requests().contains(&PACKAGE_17_0_1.to_owned()));
+        assert!(!server
+            .requests()
+            .iter()
+            .any(|r| r.starts_with("/v3/binary/latest")));
+    }
+}
@@ -1,24 +1,30 @@
 use crate::{
     config::{self},
-    version::Version,
+    provider::Provider,
+    version::{JavaVersion, Version, VersionReq},
 };
 
-use super::package_client::{DownloadResponse, PackageClient};
+use super::{
+    download::{download_client, fetch_package},
+    package_client::{DownloadResponse, PackageClient},
+};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use config::{ClientConfig, VersionRequirements};
-use indicatif::{ProgressBar, ProgressStyle};
 use log::debug;
 use reqwest::{redirect::Policy, Url};
 use serde::Deserialize;
-use tokio::{fs, io::AsyncWriteExt};
+
+const ADOPTOPENJDK_BASE_URL: &str = "https://api.adoptopenjdk.net/v3";
 
 #[derive(Debug)]
-pub struct AdoptOpenJDKClient;
+pub struct AdoptOpenJDKClient {
+    base_url: String,
+}
 
 #[derive(Debug, Deserialize)]
-struct BinariesInfo {
-    binaries: Vec<BinaryInfo>,
+pub(super) struct BinariesInfo {
+    pub(super) binaries: Vec<BinaryInfo>,
     download_count: u32,
     id: String,
     release_link: String,
@@ -27,20 +33,20 @@ struct BinariesInfo {
     timestamp: String,
     updated_at: String,
     vendor: String,
-    version_data: VersionInfo,
+    pub(super) version_data: VersionInfo,
 }
 #[derive(Debug, Deserialize)]
-struct VersionInfo {
+pub(super) struct VersionInfo {
     adopt_build_number: Option<u32>,
     build: u32,
     major: u32,
     minor: u32,
     openjdk_version: String,
     security: u32,
-    semver: String,
+    pub(super) semver: String,
 }
 #[derive(Debug, Deserialize)]
-struct BinaryInfo {
+pub(super) struct BinaryInfo {
     architecture: String,
     download_count: u32,
     heap_size: String,
@@ -48,7 +54,7 @@ struct BinaryInfo {
     installer: Option<InstallerInfo>,
     jvm_impl: String,
     os: String,
-    package: PackageInfo,
+    pub(super) package: PackageInfo,
     project: String,
     scm_ref: Option<String>,
     updated_at: String,
@@ -65,19 +71,25 @@ struct InstallerInfo {
 }
 
 #[derive(Debug, Deserialize)]
-struct PackageInfo {
-    checksum: String,
+pub(super) struct PackageInfo {
+    pub(super) checksum: String,
     checksum_link: String,
     download_count: u32,
-    link: String,
-    name: String,
-    size: u32,
+    pub(super) link: String,
+    pub(super) name: String,
+    pub(super) size: u32,
 }
 
 #[allow(dead_code)]
 impl AdoptOpenJDKClient {
     pub fn new() -> Self {
-        AdoptOpenJDKClient {}
+        AdoptOpenJDKClient::with_base_url(ADOPTOPENJDK_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        AdoptOpenJDKClient {
+            base_url: base_url.to_owned(),
+        }
     }
 }
 
@@ -94,7 +106,7 @@ pub struct AdoptOpenJDKVersion {
 #[async_trait]
 impl PackageClient for AdoptOpenJDKClient {
     fn get_base_url(&self) -> String {
-        "https://api.adoptopenjdk.net/v3".to_owned()
+        self.base_url.clone()
     }
 
     async fn get_all_version(&self, _requirements: VersionRequirements) -> Result<Vec<Version>> {
@@ -102,69 +114,85 @@ impl PackageClient for AdoptOpenJDKClient {
         debug!("Request all available versions: {}", available_releases);
         let result: AdoptOpenJDKVersion = reqwest::get(&available_releases).await?.json().await?;
 
-        convert_adoptopenjdk_to_version(result)
+        convert_adoptopenjdk_to_version(result, Provider::AdoptOpenJDK)
     }
 
     async fn download(&self, client_conf: ClientConfig) -> Result<DownloadResponse> {
         let binary_info_url = download_package_url(
             client_conf.base_url.to_owned(),
-            client_conf.requirements,
+            client_conf.requirements.clone(),
             &client_conf.version,
+            page_size(&client_conf.version_req),
+            "adoptopenjdk",
         );
 
         let binary_info: Vec<BinariesInfo> = reqwest::get(binary_info_url).await?.json().await?;
-        let bin = binary_info
-            .first()
-            .ok_or(anyhow!("Cannot get first element"))?;
+        let (bin, version) = select_release(&binary_info, &client_conf.version_req)?;
         let total_size: u32 = get_total_size(&bin);
         let package_name: &str = get_package_name(&bin);
 
-        let package_link: &str = bin
+        let package = &bin
             .binaries
             .first()
             .ok_or(anyhow!("Cannot extract package link"))?
-            .package
-            .link
-            .as_ref();
+            .package;
+        let package_link: &str = package.link.as_ref();
 
         let package_url = get_link_after_redirect(package_link).await?;
-        let tmp_dir = client_conf.download_dir;
-        let mut response = reqwest::get(package_url).await?;
-        let file_name = tmp_dir.join(client_conf.version.value);
-        debug!(
-            "Try to write in temp dir: {}",
-            &tmp_dir.as_path().to_str().expect("cannot get dir path")
-        );
-
-        let pb = ProgressBar::new(u64::from(total_size));
-        pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .progress_chars("#>-"));
-
-        let mut dest = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&file_name)
-            .await?;
-        while let Some(chunk) = response.chunk().await? {
-            dest.write_all(&chunk).await?;
-            pb.inc(chunk.len() as u64);
-        }
+        let (download_path, checksum) = fetch_package(
+            &client_conf,
+            &download_client()?,
+            package_url.as_str(),
+            package_name,
+            Some(package.checksum.clone()),
+            u64::from(total_size),
+        )
+        .await?;
         Ok(DownloadResponse {
-            download_path: file_name,
+            download_path,
             package_name: package_name.to_owned(),
+            checksum,
+            version,
+            distribution: None,
         })
     }
 }
 
-fn get_total_size(binary_info: &BinariesInfo) -> u32 {
+/// Only the latest release is needed unless a specific version or range was requested.
+pub(super) fn page_size(version_req: &VersionReq) -> u32 {
+    match version_req {
+        VersionReq::Matching(version) if version.is_feature_only() => 1,
+        _ => 50,
+    }
+}
+
+pub(super) fn select_release<'a>(
+    releases: &'a [BinariesInfo],
+    version_req: &VersionReq,
+) -> Result<(&'a BinariesInfo, JavaVersion)> {
+    releases
+        .iter()
+        .filter_map(|release| {
+            release
+                .version_data
+                .semver
+                .parse::<JavaVersion>()
+                .ok()
+                .map(|version| (release, version))
+        })
+        .filter(|(_, version)| version_req.matches(version))
+        .max_by(|(_, v1), (_, v2)| v1.cmp(v2))
+        .ok_or_else(|| anyhow!("Cannot find a release matching version {}", version_req))
+}
+
+pub(super) fn get_total_size(binary_info: &BinariesInfo) -> u32 {
     let bin = binary_info
         .binaries
         .first()
         .expect("Cannot extract package details");
     bin.package.size
 }
-fn get_package_name(binary_info: &BinariesInfo) -> &str {
+pub(super) fn get_package_name(binary_info: &BinariesInfo) -> &str {
     let bin = binary_info
         .binaries
         .first()
@@ -172,30 +200,27 @@ fn get_package_name(binary_info: &BinariesInfo) -> &str {
     bin.package.name.as_ref()
 }
 
-fn convert_adoptopenjdk_to_version(result: AdoptOpenJDKVersion) -> Result<Vec<Version>> {
+pub(super) fn convert_adoptopenjdk_to_version(
+    result: AdoptOpenJDKVersion,
+    provider: Provider,
+) -> Result<Vec<Version>> {
     let lts_versions = &result.available_lts_releases;
     let versions: Vec<Version> = result
         .available_releases
         .iter()
-        .map(|v| {
-            Version::new(
-                *v,
-                lts_versions.contains(v),
-                crate::provider::Provider::AdoptOpenJDK,
-            )
-        })
+        .map(|v| Version::new(*v, lts_versions.contains(v), provider.clone()))
         .collect();
     Ok(versions)
 }
 
-async fn get_link_after_redirect(package_link: &str) -> Result<Url> {
+pub(super) async fn get_link_after_redirect(package_link: &str) -> Result<Url> {
     let client = reqwest::Client::builder()
         .redirect(Policy::none())
         .build()?;
 
     let response = client.get(package_link).send().await?;
 
-    if response.status() == 302 {
+    if response.status().is_redirection() {
         let location_value = response
             .headers()
             .get("Location")
@@ -209,12 +234,20 @@ async fn get_link_after_redirect(package_link: &str) -> Result<Url> {
     }
 }
 
-fn download_package_url(
+pub(super) fn download_package_url(
     api_base_url: String,
     requirements: VersionRequirements,
     version: &Version,
+    page_size: u32,
+    default_vendor: &str,
 ) -> Url {
-    let url = compose_download_url(requirements, api_base_url, version);
+    let url = compose_download_url(
+        requirements,
+        api_base_url,
+        version,
+        page_size,
+        default_vendor,
+    );
     debug!("Composed url is: {}", &url.to_string());
     url
 }
@@ -223,26 +256,31 @@ fn compose_download_url(
     requirements: VersionRequirements,
     api_base_url: String,
     version: &Version,
+    page_size: u32,
+    default_vendor: &str,
 ) -> Url {
-    let os = if cfg!(target_os = "windows") {
-        "windows"
-    } else if cfg!(target_os = "mac") {
-        "mac"
-    } else {
-        "linux"
-    };
-
     Url::parse(&format!(
-        "{}/assets/feature_releases/{}/{}?architecture={}&heap_size={}&image_type={}&jvm_impl={}&os={}&page=0&page_size=1&project={}&sort_method=DEFAULT&sort_order=DESC&vendor={}",
+        "{}/assets/feature_releases/{}/{}?architecture={}&heap_size={}&image_type={}&jvm_impl={}&os={}&page=0&page_size={}&project={}&sort_method=DEFAULT&sort_order=DESC&vendor={}",
         api_base_url,
-        version,
+        version.value.feature,
         requirements.release_type.unwrap_or("ga".to_owned()),
         requirements.arch.unwrap_or("x64".to_owned()),
         requirements.heap_size.unwrap_or("normal".to_owned()),
         requirements.image_type.unwrap_or("jdk".to_owned()),
         requirements.jvm_impl.unwrap_or("hotspot".to_owned()),
-        requirements.os.unwrap_or(os.to_owned()),
+        requirements.os.unwrap_or(default_os().to_owned()),
+        page_size,
         requirements.project.unwrap_or("jdk".to_owned()),
-        requirements.vendor.unwrap_or("adoptopenjdk".to_owned()),
+        requirements.vendor.unwrap_or(default_vendor.to_owned()),
         )).expect("Cannot create download url!")
 }
+
+pub(super) fn default_os() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "mac"
+    } else {
+        "linux"
+    }
+}
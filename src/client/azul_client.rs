@@ -2,19 +2,24 @@ use std::collections::HashSet;
 
 use crate::{
     config::{ClientConfig, VersionRequirements},
-    version::Version,
+    version::{JavaVersion, Version, VersionReq},
 };
 
-use super::package_client::{DownloadResponse, PackageClient};
-use anyhow::Result;
+use super::{
+    download::{download_client, fetch_package},
+    package_client::{DownloadResponse, PackageClient},
+};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, warn};
 use serde::Deserialize;
-use tokio::{fs, io::AsyncWriteExt};
+
+const AZUL_BASE_URL: &str = "https://api.azul.com/zulu/download/community/v1.0";
 
 #[derive(Debug)]
-pub struct AzulClient;
+pub struct AzulClient {
+    base_url: String,
+}
 
 #[derive(Debug, Deserialize)]
 pub struct AzulPackageDetails {
@@ -49,14 +54,20 @@ pub struct AzulPackage {
 
 impl AzulClient {
     pub fn new() -> Self {
-        AzulClient {}
+        AzulClient::with_base_url(AZUL_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        AzulClient {
+            base_url: base_url.to_owned(),
+        }
     }
 }
 
 #[async_trait]
 impl PackageClient for AzulClient {
     fn get_base_url(&self) -> String {
-        "https://api.azul.com/zulu/download/community/v1.0".to_owned()
+        self.base_url.clone()
     }
 
     async fn get_all_version(&self, requirements: VersionRequirements) -> Result<Vec<Version>> {
@@ -79,54 +90,88 @@ impl PackageClient for AzulClient {
         );
         let mut versions: Vec<Version> =
             combine_versions_info(major_releases, lts_major_releases.ok());
-        versions.sort_by(|v1, v2| {
-            v1.value
-                .parse::<u32>()
-                .unwrap_or(0)
-                .cmp(&v2.value.parse::<u32>().unwrap_or(0))
-        });
+        versions.sort_by(|v1, v2| v1.value.cmp(&v2.value));
         Ok(versions)
     }
 
     async fn download(&self, client_conf: ClientConfig) -> Result<DownloadResponse> {
-        let version_value = &client_conf.version.value;
-        let tmp_dir = &client_conf.download_dir.clone();
-        let file_name = tmp_dir.join(version_value);
-
-        let details_url = compose_details_url(
-            self.get_base_url().as_ref(),
-            version_value.as_ref(),
-            client_conf.requirements,
-        );
-        let package_details: AzulPackageDetails = reqwest::get(&details_url).await?.json().await?;
+        let version_value = client_conf.version.value.to_string();
 
-        let mut response = reqwest::get(&package_details.url).await?;
-        debug!(
-            "Try to write in temp dir: {}",
-            &tmp_dir.as_path().to_str().expect("cannot get dir path")
-        );
+        let package_details = match &client_conf.version_req {
+            VersionReq::Matching(version) if version.is_feature_only() => {
+                let details_url = compose_details_url(
+                    self.get_base_url().as_ref(),
+                    version_value.as_ref(),
+                    client_conf.requirements.clone(),
+                );
+                reqwest::get(&details_url).await?.json().await?
+            }
+            version_req => {
+                self.get_matching_package_details(
+                    client_conf.version.value.feature,
+                    version_req,
+                    client_conf.requirements.clone(),
+                )
+                .await?
+            }
+        };
+        let version = JavaVersion::from_components(&package_details.jdk_version)?
+            .with_zulu_version(package_details.zulu_version.clone());
 
-        let pb = ProgressBar::new(u64::from(package_details.size));
-        pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .progress_chars("#>-"));
-
-        let mut dest = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&file_name)
-            .await?;
-        while let Some(chunk) = response.chunk().await? {
-            dest.write_all(&chunk).await?;
-            pb.inc(chunk.len() as u64);
-        }
+        let (download_path, checksum) = fetch_package(
+            &client_conf,
+            &download_client()?,
+            &package_details.url,
+            &package_details.name,
+            Some(package_details.sha256_hash.clone()),
+            u64::from(package_details.size),
+        )
+        .await?;
         Ok(DownloadResponse {
-            download_path: file_name,
+            download_path,
             package_name: package_details.name,
+            checksum,
+            version,
+            distribution: None,
         })
     }
 }
 
+impl AzulClient {
+    async fn get_matching_package_details(
+        &self,
+        feature: u32,
+        version_req: &VersionReq,
+        requirements: VersionRequirements,
+    ) -> Result<AzulPackageDetails> {
+        let bundles_url = format!(
+            "{}&jdk_version={}",
+            compose_all_version_url(self.get_base_url().as_ref(), requirements),
+            feature
+        );
+        debug!("Request bundles: {}", bundles_url);
+        let bundles: Vec<AzulPackage> = reqwest::get(&bundles_url).await?.json().await?;
+        let bundle_id = select_bundle(bundles, version_req)?;
+
+        let details_url = format!("{}/bundles/{}/", self.get_base_url(), bundle_id);
+        Ok(reqwest::get(&details_url).await?.json().await?)
+    }
+}
+
+fn select_bundle(bundles: Vec<AzulPackage>, version_req: &VersionReq) -> Result<u32> {
+    bundles
+        .into_iter()
+        .filter_map(|bundle| {
+            JavaVersion::from_components(&bundle.jdk_version)
+                .ok()
+                .map(|version| (bundle.id, version))
+        })
+        .filter(|(_, version)| version_req.matches(version))
+        .max_by(|(_, v1), (_, v2)| v1.cmp(v2))
+        .map(|(id, _)| id)
+        .ok_or_else(|| anyhow!("Cannot find a release matching version {}", version_req))
+}
+
 pub fn combine_versions_info(
     major_versions: HashSet<u32>,
     lts_major_releases: Option<HashSet<u32>>,
@@ -179,7 +224,7 @@ async fn get_lts_releases(bundles_url: &str) -> Result<HashSet<u32>> {
 mod tests {
     use crate::config::VersionRequirements;
 
-    use super::{compose_all_version_url, compose_details_url};
+    use super::{compose_all_version_url, compose_details_url, select_bundle, AzulPackage};
 
     const BASE_URL: &str = "https://api.azul.com/zulu/download/community/v1.0";
 
@@ -193,6 +238,9 @@ mod tests {
             heap_size: None,
             vendor: None,
             project: None,
+            github_repo: None,
+            asset_pattern: None,
+            distribution: None,
         }
     }
 
@@ -213,4 +261,35 @@ mod tests {
         );
         assert_eq!(url, expected_url)
     }
+
+    fn bundle(id: u32, jdk_version: Vec<u32>) -> AzulPackage {
+        AzulPackage {
+            id,
+            name: format!("zulu-{}.zip", id),
+            url: format!("https://cdn.azul.com/zulu/bin/zulu-{}.zip", id),
+            jdk_version,
+            zulu_version: vec![],
+        }
+    }
+
+    #[test]
+    fn select_bundle_should_pick_highest_matching_version() {
+        let bundles = || {
+            vec![
+                bundle(1, vec![11, 0, 12, 7]),
+                bundle(2, vec![11, 0, 20, 8]),
+                bundle(3, vec![11, 0, 11, 9]),
+            ]
+        };
+
+        assert_eq!(
+            select_bundle(bundles(), &"11.0.12".parse().unwrap()).unwrap(),
+            1
+        );
+        assert_eq!(
+            select_bundle(bundles(), &">=11".parse().unwrap()).unwrap(),
+            2
+        );
+        assert!(select_bundle(bundles(), &"11.0.13".parse().unwrap()).is_err());
+    }
 }
@@ -0,0 +1,359 @@
+use crate::{
+    config::{ClientConfig, VersionRequirements},
+    provider::Provider,
+    version::{JavaVersion, Version, VersionReq},
+};
+
+use super::{
+    download::{download_client, fetch_package},
+    package_client::{DownloadResponse, PackageClient},
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::debug;
+use serde::{de::DeserializeOwned, Deserialize};
+
+const DISCO_BASE_URL: &str = "https://api.foojay.io/disco/v3.0";
+const DEFAULT_DISTRIBUTION: &str = "temurin";
+
+/// Client for the foojay Disco API, which serves packages of many OpenJDK distributions.
+#[derive(Debug)]
+pub struct DiscoClient {
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoResponse<T> {
+    result: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MajorVersion {
+    major_version: u32,
+    term_of_support: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoPackage {
+    id: String,
+    java_version: String,
+    distribution: String,
+    filename: String,
+    size: u64,
+    #[serde(default)]
+    javafx_bundled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageInfo {
+    direct_download_uri: String,
+    #[serde(default)]
+    checksum: String,
+    #[serde(default)]
+    checksum_type: String,
+    #[serde(default)]
+    checksum_uri: String,
+}
+
+impl DiscoClient {
+    pub fn new() -> Self {
+        DiscoClient::with_base_url(DISCO_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        DiscoClient {
+            base_url: base_url.to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl PackageClient for DiscoClient {
+    fn get_base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    async fn get_all_version(&self, requirements: VersionRequirements) -> Result<Vec<Version>> {
+        let major_versions_url = format!(
+            "{}/major_versions?ea={}&ga=true&maintained=true&include_versions=false",
+            self.get_base_url(),
+            is_early_access(&requirements)
+        );
+        debug!("Request all available versions: {}", major_versions_url);
+        let major_versions: Vec<MajorVersion> = get_result(&major_versions_url).await?;
+
+        let mut versions: Vec<Version> = major_versions
+            .iter()
+            .map(|v| Version::new(v.major_version, is_lts(&v.term_of_support), Provider::Disco))
+            .collect();
+        versions.sort();
+        Ok(versions)
+    }
+
+    async fn download(&self, client_conf: ClientConfig) -> Result<DownloadResponse> {
+        let packages_url = compose_packages_url(
+            &client_conf.base_url,
+            &client_conf.requirements,
+            &client_conf.version_req,
+        );
+        debug!("Request packages: {}", packages_url);
+        let packages: Vec<DiscoPackage> = get_result(&packages_url).await?;
+        let (package, version) = select_package(&packages, &client_conf.version_req)?;
+        debug!(
+            "Selected package {} of {}",
+            package.filename, package.distribution
+        );
+
+        let info_url = format!("{}/ids/{}", client_conf.base_url, package.id);
+        let info = get_result::<PackageInfo>(&info_url)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Cannot get download details for {}", package.filename))?;
+        let expected_checksum = package_checksum(&info).await?;
+
+        let (download_path, checksum) = fetch_package(
+            &client_conf,
+            &download_client()?,
+            &info.direct_download_uri,
+            &package.filename,
+            expected_checksum,
+            package.size,
+        )
+        .await?;
+
+        Ok(DownloadResponse {
+            download_path,
+            package_name: package.filename.clone(),
+            checksum,
+            version,
+            distribution: Some(package.distribution.clone()),
+        })
+    }
+}
+
+async fn get_result<T: DeserializeOwned>(url: &str) -> Result<Vec<T>> {
+    let response: DiscoResponse<T> = reqwest::get(url).await?.error_for_status()?.json().await?;
+    Ok(response.result)
+}
+
+fn is_lts(term_of_support: &str) -> bool {
+    term_of_support.eq_ignore_ascii_case("lts")
+}
+
+fn is_early_access(requirements: &VersionRequirements) -> bool {
+    requirements.release_type.as_deref() == Some("ea")
+}
+
+/// Disco names macOS `macos` where the other providers use `mac`.
+fn operating_system(requirements: &VersionRequirements) -> &str {
+    match requirements.os.as_deref() {
+        Some("mac") => "macos",
+        Some(os) => os,
+        None if cfg!(target_os = "windows") => "windows",
+        None if cfg!(target_os = "macos") => "macos",
+        None => "linux",
+    }
+}
+
+fn compose_packages_url(
+    base_url: &str,
+    requirements: &VersionRequirements,
+    version_req: &VersionReq,
+) -> String {
+    let archive_type = if cfg!(target_os = "windows") {
+        "zip"
+    } else {
+        "tar.gz"
+    };
+    // only the newest package is needed unless a specific version or range was requested
+    let latest = match version_req {
+        VersionReq::Matching(version) if version.is_feature_only() => "&latest=available",
+        _ => "",
+    };
+
+    format!(
+        "{}/packages?jdk_version={}&distribution={}&architecture={}&archive_type={}&package_type={}&operating_system={}&release_status={}&directly_downloadable=true{}",
+        base_url,
+        version_req.version().feature,
+        requirements.distribution.as_deref().unwrap_or(DEFAULT_DISTRIBUTION),
+        requirements.arch.as_deref().unwrap_or("x64"),
+        archive_type,
+        requirements.image_type.as_deref().unwrap_or("jdk"),
+        operating_system(requirements),
+        if is_early_access(requirements) { "ea" } else { "ga" },
+        latest,
+    )
+}
+
+/// Pick the highest matching version, preferring packages without JavaFX.
+fn select_package<'a>(
+    packages: &'a [DiscoPackage],
+    version_req: &VersionReq,
+) -> Result<(&'a DiscoPackage, JavaVersion)> {
+    packages
+        .iter()
+        .filter_map(|package| {
+            package
+                .java_version
+                .parse::<JavaVersion>()
+                .ok()
+                .map(|version| (package, version))
+        })
+        .filter(|(_, version)| version_req.matches(version))
+        .max_by(|(p1, v1), (p2, v2)| {
+            v1.cmp(v2)
+                .then_with(|| p2.javafx_bundled.cmp(&p1.javafx_bundled))
+        })
+        .ok_or_else(|| anyhow!("Cannot find a package matching version {}", version_req))
+}
+
+/// Use the SHA-256 checksum announced by the API or published next to the package.
+async fn package_checksum(info: &PackageInfo) -> Result<Option<String>> {
+    let is_sha256 =
+        info.checksum_type.is_empty() || info.checksum_type.eq_ignore_ascii_case("sha256");
+    if !is_sha256 {
+        debug!("Unsupported checksum type {}", info.checksum_type);
+        return Ok(None);
+    }
+    if !info.checksum.is_empty() {
+        return Ok(Some(info.checksum.clone()));
+    }
+    if info.checksum_uri.is_empty() {
+        return Ok(None);
+    }
+
+    debug!("Request checksum: {}", info.checksum_uri);
+    let content = reqwest::get(&info.checksum_uri)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(content.split_whitespace().next().map(str::to_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compose_packages_url, DiscoClient};
+    use crate::{
+        cache::{PackageCache, DEFAULT_MAX_SIZE},
+        checksum::sha256_file,
+        client::{
+            package_client::PackageClient,
+            test_server::{Route, TestServer},
+        },
+        config::{ClientConfig, VersionRequirements},
+        provider::Provider,
+        test_support::create_fixture_archive,
+        version::{Version, VersionReq},
+    };
+    use std::fs::read;
+    use tempdir::TempDir;
+
+    const MAJOR_VERSIONS: &str = include_str!("fixtures/disco/major_versions.json");
+    const PACKAGES_CORRETTO_17: &str = include_str!("fixtures/disco/packages_corretto_17.json");
+    const PACKAGE_INFO: &str = include_str!("fixtures/disco/ids_corretto_17.0.2.json");
+    const PACKAGE_ID: &str = "a2a7b5c6d1ecb01bd9c8b4b5d6e0a3f5";
+    const RECORDED_CHECKSUM: &str =
+        "ff4a2c04a6a6e4a1b1dcdaea3f0ba1d4c5a9ee84ef6adfbbe4c2d4e8e1d1b4d6";
+    const PACKAGE_PATH: &str =
+        "/downloads/resources/17.0.2.8.1/amazon-corretto-17.0.2.8.1-linux-x64.tar.gz";
+
+    fn requirements() -> VersionRequirements {
+        VersionRequirements {
+            os: Some("linux".to_owned()),
+            arch: Some("x64".to_owned()),
+            distribution: Some("corretto".to_owned()),
+            ..VersionRequirements::default()
+        }
+    }
+
+    #[test]
+    fn compose_packages_url_should_map_requirements() {
+        let req: VersionReq = "17".parse().unwrap();
+        let url = compose_packages_url(
+            "https://api.foojay.io/disco/v3.0",
+            &VersionRequirements {
+                os: Some("mac".to_owned()),
+                ..requirements()
+            },
+            &req,
+        );
+        assert!(url.starts_with("https://api.foojay.io/disco/v3.0/packages?jdk_version=17&distribution=corretto&architecture=x64"));
+        assert!(url.contains("&package_type=jdk&operating_system=macos&release_status=ga"));
+        assert!(url.ends_with("&latest=available"));
+
+        let req: VersionReq = ">=17.0.1".parse().unwrap();
+        let url = compose_packages_url("", &requirements(), &req);
+        assert!(!url.contains("latest="));
+    }
+
+    #[tokio::test]
+    async fn get_all_version_should_read_major_versions() {
+        let server = TestServer::start().await;
+        server.route("/disco/v3.0/major_versions", Route::ok(MAJOR_VERSIONS));
+        let client = DiscoClient::with_base_url(&format!("{}/disco/v3.0", server.base_url));
+
+        let versions = client.get_all_version(requirements()).await.unwrap();
+
+        let features: Vec<u32> = versions.iter().map(|v| v.value.feature).collect();
+        assert_eq!(features, vec![8, 11, 17, 18]);
+        let lts: Vec<u32> = versions
+            .iter()
+            .filter(|v| v.is_lts())
+            .map(|v| v.value.feature)
+            .collect();
+        assert_eq!(lts, vec![8, 11, 17]);
+    }
+
+    #[tokio::test]
+    async fn download_should_record_distribution_and_verify_checksum() {
+        let dir = TempDir::new("jvc-disco").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let server = TestServer::start().await;
+        server.route("/disco/v3.0/packages", Route::ok(PACKAGES_CORRETTO_17));
+        server.route(
+            &format!("/disco/v3.0/ids/{}", PACKAGE_ID),
+            Route::ok(
+                PACKAGE_INFO
+                    .replace("https://corretto.aws", &server.base_url)
+                    .replace(RECORDED_CHECKSUM, &sha256_file(&archive).unwrap()),
+            ),
+        );
+        server.route(PACKAGE_PATH, Route::ok(read(&archive).unwrap()));
+        let client = DiscoClient::with_base_url(&format!("{}/disco/v3.0", server.base_url));
+        let client_config = || ClientConfig {
+            requirements: requirements(),
+            download_dir: dir.path().to_owned(),
+            base_url: format!("{}/disco/v3.0", server.base_url),
+            version: Version::new(17, true, Provider::Disco),
+            version_req: "17.0".parse().unwrap(),
+            cache: Some(PackageCache::new(
+                dir.path().join("cache"),
+                DEFAULT_MAX_SIZE,
+            )),
+        };
+
+        let response = client.download(client_config()).await.unwrap();
+
+        assert_eq!(response.version.to_string(), "17.0.2+8");
+        assert_eq!(response.distribution, Some("corretto".to_owned()));
+        assert_eq!(
+            response.package_name,
+            "amazon-corretto-17.0.2.8.1-linux-x64.tar.gz"
+        );
+        assert!(response.checksum.verify(&response.package_name).is_ok());
+        assert!(server.requests()[0].contains("distribution=corretto"));
+        assert!(response.download_path.starts_with(dir.path().join("cache")));
+
+        let cached = client.download(client_config()).await.unwrap();
+        assert_eq!(cached.download_path, response.download_path);
+        assert!(cached.checksum.verify(&cached.package_name).is_ok());
+        let package_requests = server
+            .requests()
+            .iter()
+            .filter(|path| path.as_str() == PACKAGE_PATH)
+            .count();
+        assert_eq!(package_requests, 1);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{checksum::Checksum, config::ClientConfig};
+use anyhow::{anyhow, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{debug, info, warn};
+use reqwest::{header, Client, ClientBuilder, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::OsString,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncSeekExt, AsyncWriteExt},
+    time::{sleep, timeout},
+};
+
+/// Give up connecting to unreachable servers after this long.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client builder with the timeouts used for every download.
+pub fn client_builder() -> ClientBuilder {
+    Client::builder().connect_timeout(CONNECT_TIMEOUT)
+}
+
+pub fn download_client() -> Result<Client> {
+    Ok(client_builder().build()?)
+}
+
+/// How often a dropped download is attempted again and how long to wait in between.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    /// Delay before the first retry, doubled for every following one
+    pub backoff: Duration,
+    /// Time without any response data after which the connection counts as dropped
+    pub read_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 5,
+            backoff: Duration::from_secs(1),
+            read_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Why an attempt stopped, with the error reported when no attempt is left.
+enum Failure {
+    Retry(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+/// Reuse the cached package with `expected_checksum` or download it from `url`.
+///
+/// Downloaded packages matching the expected checksum are moved into the cache, the returned path
+/// points to the package wherever it ended up.
+pub async fn fetch_package(
+    client_conf: &ClientConfig,
+    client: &Client,
+    url: &str,
+    package_name: &str,
+    expected_checksum: Option<String>,
+    total_size: u64,
+) -> Result<(PathBuf, Checksum)> {
+    let cache = client_conf.cache.as_ref();
+    if let (Some(cache), Some(expected)) = (cache, &expected_checksum) {
+        if let Some(path) = cache.get(expected)? {
+            info!("Using cached package {}", package_name);
+            let checksum = Checksum {
+                expected: expected_checksum.clone(),
+                actual: expected.trim().to_lowercase(),
+            };
+            return Ok((path, checksum));
+        }
+    }
+
+    let file_name = client_conf
+        .download_dir
+        .join(client_conf.version.value.to_string());
+    let hasher = download_package(client, url, &file_name, total_size).await?;
+    let checksum = Checksum::new(expected_checksum, hasher);
+    let path = match cache {
+        Some(cache) if checksum.verify(package_name).is_ok() => {
+            cache.store(&checksum.actual, package_name, &file_name)?
+        }
+        _ => file_name,
+    };
+    Ok((path, checksum))
+}
+
+/// Download `url` to `file_name` and return the SHA-256 hasher of its content.
+///
+/// The body is written to `<file_name>.part`, which is renamed once complete. Dropped connections
+/// are retried with backoff and resumed with a `Range` request when the server supports it.
+pub async fn download_package(
+    client: &Client,
+    url: &str,
+    file_name: &Path,
+    total_size: u64,
+) -> Result<Sha256> {
+    download_package_with(client, url, file_name, total_size, &RetryPolicy::default()).await
+}
+
+pub async fn download_package_with(
+    client: &Client,
+    url: &str,
+    file_name: &Path,
+    total_size: u64,
+    retry: &RetryPolicy,
+) -> Result<Sha256> {
+    debug!("Try to write package from {} to: {:?}", url, file_name);
+    let part_file = part_file_name(file_name);
+    let mut progress = Progress {
+        dest: fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&part_file)
+            .await?,
+        hasher: Sha256::new(),
+        written: 0,
+        bar: progress_bar(total_size),
+    };
+
+    let mut attempt = 1;
+    loop {
+        match progress
+            .attempt(client, url, total_size, retry.read_timeout)
+            .await
+        {
+            Ok(()) => break,
+            Err(Failure::Retry(e)) if attempt < retry.attempts => {
+                let delay = retry.backoff * 2u32.pow(attempt - 1);
+                warn!(
+                    "Download interrupted after {} bytes: {}. Retrying in {:?}",
+                    progress.written, e, delay
+                );
+                sleep(delay).await;
+                attempt += 1;
+            }
+            Err(Failure::Retry(e)) | Err(Failure::Fatal(e)) => {
+                progress.bar.abandon();
+                drop(progress.dest);
+                if let Err(remove_error) = fs::remove_file(&part_file).await {
+                    warn!("Cannot remove {:?}: {}", part_file, remove_error);
+                }
+                return Err(e.context(format!("Cannot download {}", url)));
+            }
+        }
+    }
+    progress.bar.finish();
+    progress.dest.flush().await?;
+    drop(progress.dest);
+
+    fs::rename(&part_file, file_name).await?;
+    Ok(progress.hasher)
+}
+
+struct Progress {
+    dest: File,
+    hasher: Sha256,
+    written: u64,
+    bar: ProgressBar,
+}
+
+impl Progress {
+    async fn attempt(
+        &mut self,
+        client: &Client,
+        url: &str,
+        total_size: u64,
+        read_timeout: Duration,
+    ) -> Result<(), Failure> {
+        let mut request = client.get(url);
+        if self.written > 0 {
+            request = request.header(header::RANGE, format!("bytes={}-", self.written));
+        }
+        let mut response = timeout(read_timeout, request.send())
+            .await
+            .map_err(|_| Failure::Retry(anyhow!("No response within {:?}", read_timeout)))?
+            .map_err(|e| Failure::Retry(e.into()))?;
+        check_status(&response)?;
+
+        if self.written > 0 && !resumes_at(&response, self.written) {
+            debug!("Server does not support ranges, restart download");
+            self.restart().await.map_err(Failure::Fatal)?;
+        }
+
+        let expected_length = response
+            .content_length()
+            .map(|length| self.written + length);
+        if let Some(length) = expected_length.filter(|length| *length != total_size) {
+            debug!(
+                "Server announced {} bytes instead of {} bytes",
+                length, total_size
+            );
+            self.bar.set_length(length);
+        }
+
+        loop {
+            let chunk = timeout(read_timeout, response.chunk())
+                .await
+                .map_err(|_| Failure::Retry(anyhow!("No data received for {:?}", read_timeout)))?;
+            match chunk {
+                Ok(Some(chunk)) => {
+                    self.dest
+                        .write_all(&chunk)
+                        .await
+                        .map_err(|e| Failure::Fatal(e.into()))?;
+                    self.hasher.update(&chunk);
+                    self.written += chunk.len() as u64;
+                    self.bar.set_position(self.written);
+                }
+                Ok(None) => break,
+                Err(e) => return Err(Failure::Retry(e.into())),
+            }
+        }
+
+        match expected_length {
+            Some(length) if self.written < length => Err(Failure::Retry(anyhow!(
+                "Connection closed after {} of {} bytes",
+                self.written,
+                length
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn restart(&mut self) -> Result<()> {
+        self.dest.set_len(0).await?;
+        // set_len keeps the cursor, so move it back explicitly
+        self.dest.seek(SeekFrom::Start(0)).await?;
+        self.hasher = Sha256::new();
+        self.written = 0;
+        self.bar.set_position(0);
+        Ok(())
+    }
+}
+
+/// Server errors and throttling are worth retrying, other errors are not.
+fn check_status(response: &Response) -> Result<(), Failure> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let error = anyhow!("Server responded with {}", status);
+    if status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+    {
+        Err(Failure::Retry(error))
+    } else {
+        Err(Failure::Fatal(error))
+    }
+}
+
+/// A `206 Partial Content` response continuing at `offset`.
+fn resumes_at(response: &Response, offset: u64) -> bool {
+    let content_range = response
+        .headers()
+        .get(header::CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    response.status() == StatusCode::PARTIAL_CONTENT
+        && content_range.starts_with(&format!("bytes {}-", offset))
+}
+
+fn part_file_name(file_name: &Path) -> PathBuf {
+    let mut name = OsString::from(file_name.as_os_str());
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+fn progress_bar(total_size: u64) -> ProgressBar {
+    let pb = ProgressBar::new(total_size);
+    pb.set_style(ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .progress_chars("#>-"));
+    pb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{download_package_with, part_file_name, RetryPolicy};
+    use crate::client::test_server::{Route, TestServer};
+    use reqwest::Client;
+    use sha2::{Digest, Sha256};
+    use std::{fs::read, time::Duration};
+    use tempdir::TempDir;
+
+    fn body() -> Vec<u8> {
+        (0..100_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn retry() -> RetryPolicy {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(10),
+            read_timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn sha256(content: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(content))
+    }
+
+    #[tokio::test]
+    async fn download_should_resume_dropped_connection_with_range() {
+        let dir = TempDir::new("jvc-download").unwrap();
+        let file_name = dir.path().join("17.0.2");
+        let server = TestServer::start().await;
+        server.route_sequence(
+            "/package.tar.gz",
+            vec![
+                Route::ok(body()).with_ranges().cut_after(30_000),
+                Route::ok(body()).with_ranges(),
+            ],
+        );
+        let url = format!("{}/package.tar.gz", server.base_url);
+
+        let hasher = download_package_with(&Client::new(), &url, &file_name, 100_000, &retry())
+            .await
+            .unwrap();
+
+        assert_eq!(read(&file_name).unwrap(), body());
+        assert_eq!(format!("{:x}", hasher.finalize()), sha256(&body()));
+        assert!(!part_file_name(&file_name).exists());
+        assert_eq!(server.ranges(), vec![None, Some("bytes=30000-".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn download_should_restart_when_ranges_are_not_supported() {
+        let dir = TempDir::new("jvc-download").unwrap();
+        let file_name = dir.path().join("17.0.2");
+        let server = TestServer::start().await;
+        server.route_sequence(
+            "/package.tar.gz",
+            vec![Route::ok(body()).cut_after(50_000), Route::ok(body())],
+        );
+        let url = format!("{}/package.tar.gz", server.base_url);
+
+        let hasher = download_package_with(&Client::new(), &url, &file_name, 0, &retry())
+            .await
+            .unwrap();
+
+        assert_eq!(read(&file_name).unwrap(), body());
+        assert_eq!(format!("{:x}", hasher.finalize()), sha256(&body()));
+    }
+
+    #[tokio::test]
+    async fn download_should_fail_after_last_attempt() {
+        let dir = TempDir::new("jvc-download").unwrap();
+        let file_name = dir.path().join("17.0.2");
+        let server = TestServer::start().await;
+        server.route(
+            "/package.tar.gz",
+            Route::ok(body()).with_ranges().cut_after(10),
+        );
+        let url = format!("{}/package.tar.gz", server.base_url);
+
+        let result =
+            download_package_with(&Client::new(), &url, &file_name, 100_000, &retry()).await;
+
+        assert!(result.is_err());
+        assert_eq!(server.requests().len(), 3);
+        assert!(!file_name.exists());
+        assert!(!part_file_name(&file_name).exists());
+    }
+
+    #[tokio::test]
+    async fn download_should_retry_stalled_connection() {
+        let dir = TempDir::new("jvc-download").unwrap();
+        let file_name = dir.path().join("17.0.2");
+        let server = TestServer::start().await;
+        server.route_sequence(
+            "/package.tar.gz",
+            vec![
+                Route::ok(body()).with_ranges().stall_after(20_000),
+                Route::ok(body()).with_ranges(),
+            ],
+        );
+        let url = format!("{}/package.tar.gz", server.base_url);
+        let retry = RetryPolicy {
+            read_timeout: Duration::from_millis(200),
+            ..retry()
+        };
+
+        download_package_with(&Client::new(), &url, &file_name, 100_000, &retry)
+            .await
+            .unwrap();
+
+        assert_eq!(read(&file_name).unwrap(), body());
+        assert_eq!(server.ranges(), vec![None, Some("bytes=20000-".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn download_should_not_retry_client_errors() {
+        let dir = TempDir::new("jvc-download").unwrap();
+        let file_name = dir.path().join("17.0.2");
+        let server = TestServer::start().await;
+        let url = format!("{}/missing.tar.gz", server.base_url);
+
+        let result = download_package_with(&Client::new(), &url, &file_name, 0, &retry()).await;
+
+        assert!(result.is_err());
+        assert_eq!(server.requests().len(), 1);
+        assert!(!part_file_name(&file_name).exists());
+    }
+}
@@ -1,34 +1,433 @@
 use crate::{
     config::{ClientConfig, VersionRequirements},
-    version::Version,
+    provider::Provider,
+    version::{is_lts_feature, JavaVersion, Version, VersionReq},
 };
 
-use super::package_client::{DownloadResponse, PackageClient};
+use super::{
+    adoptopenjdk_client::default_os,
+    download::{client_builder, fetch_package},
+    package_client::{DownloadResponse, PackageClient},
+};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use log::debug;
+use reqwest::{header, Client};
+use serde::Deserialize;
+use std::env;
+
+const GITHUB_BASE_URL: &str = "https://api.github.com";
+const DEFAULT_REPO: &str = "SAP/SapMachine";
+const DEFAULT_ASSET_PATTERN: &str = "*{image_type}*{os}*{arch}*.{ext}";
+const PER_PAGE: usize = 100;
+const MAX_PAGES: usize = 5;
+
+/// Client for JDK builds published as GitHub release assets.
 #[derive(Debug)]
-pub struct GithubClient {}
+pub struct GithubClient {
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    prerelease: bool,
+    draft: bool,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    size: u64,
+    browser_download_url: String,
+}
 
 impl GithubClient {
     pub fn new() -> Self {
-        GithubClient {}
+        GithubClient::with_base_url(GITHUB_BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        GithubClient {
+            base_url: base_url.to_owned(),
+        }
     }
 }
 
 #[async_trait]
 impl PackageClient for GithubClient {
     fn get_base_url(&self) -> String {
-        "".to_owned()
+        self.base_url.clone()
+    }
+
+    async fn get_all_version(&self, requirements: VersionRequirements) -> Result<Vec<Version>> {
+        let releases = fetch_releases(&self.get_base_url(), &requirements).await?;
+        let pattern = asset_pattern(&requirements);
+        let include_pre = include_prereleases(&requirements);
+
+        let mut versions: Vec<Version> = releases
+            .iter()
+            .filter_map(|release| matching_release(release, &pattern, include_pre))
+            .map(|(_, _, version)| {
+                let is_lts = is_lts_feature(version.feature);
+                Version::from_java_version(version, is_lts, Provider::Github)
+            })
+            .collect();
+        versions.sort();
+        versions.dedup();
+        Ok(versions)
+    }
+
+    async fn download(&self, client_conf: ClientConfig) -> Result<DownloadResponse> {
+        let requirements = &client_conf.requirements;
+        let releases = fetch_releases(&client_conf.base_url, requirements).await?;
+        let (release, asset, version) = select_release(
+            &releases,
+            &client_conf.version_req,
+            &asset_pattern(requirements),
+            include_prereleases(requirements),
+        )?;
+        debug!(
+            "Selected asset {} from release {}",
+            asset.name, release.tag_name
+        );
+
+        let expected_checksum = match checksum_asset(release, asset) {
+            Some(checksum_asset) => Some(fetch_checksum(checksum_asset).await?),
+            None => None,
+        };
+
+        let (download_path, checksum) = fetch_package(
+            &client_conf,
+            &http_client()?,
+            &asset.browser_download_url,
+            &asset.name,
+            expected_checksum,
+            asset.size,
+        )
+        .await?;
+
+        Ok(DownloadResponse {
+            download_path,
+            package_name: asset.name.clone(),
+            checksum,
+            version,
+            distribution: None,
+        })
+    }
+}
+
+/// GitHub rejects requests without a user agent. `GITHUB_TOKEN` raises the rate limit.
+fn http_client() -> Result<Client> {
+    let mut headers = header::HeaderMap::new();
+    if let Ok(token) = env::var("GITHUB_TOKEN") {
+        headers.insert(
+            header::AUTHORIZATION,
+            header::HeaderValue::from_str(&format!("token {}", token))?,
+        );
+    }
+    Ok(client_builder()
+        .user_agent(concat!("jvc/", env!("CARGO_PKG_VERSION")))
+        .default_headers(headers)
+        .build()?)
+}
+
+async fn fetch_releases(
+    base_url: &str,
+    requirements: &VersionRequirements,
+) -> Result<Vec<GithubRelease>> {
+    let repo = requirements.github_repo.as_deref().unwrap_or(DEFAULT_REPO);
+    let client = http_client()?;
+    let mut releases = vec![];
+    for page in 1..=MAX_PAGES {
+        let releases_url = format!(
+            "{}/repos/{}/releases?per_page={}&page={}",
+            base_url, repo, PER_PAGE, page
+        );
+        debug!("Request releases: {}", releases_url);
+        let page_releases: Vec<GithubRelease> = client
+            .get(&releases_url)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| anyhow!("Cannot list releases of {}: {}", repo, e))?
+            .json()
+            .await?;
+        let is_last_page = page_releases.len() < PER_PAGE;
+        releases.extend(page_releases);
+        if is_last_page {
+            break;
+        }
     }
+    Ok(releases)
+}
+
+async fn fetch_checksum(checksum_asset: &GithubAsset) -> Result<String> {
+    debug!("Request checksum: {}", checksum_asset.browser_download_url);
+    let content = http_client()?
+        .get(&checksum_asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    content
+        .split_whitespace()
+        .next()
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow!("Checksum file {} is empty", checksum_asset.name))
+}
+
+fn include_prereleases(requirements: &VersionRequirements) -> bool {
+    requirements.release_type.as_deref() == Some("ea")
+}
+
+fn asset_pattern(requirements: &VersionRequirements) -> String {
+    let ext = if cfg!(target_os = "windows") {
+        "zip"
+    } else {
+        "tar.gz"
+    };
+    requirements
+        .asset_pattern
+        .as_deref()
+        .unwrap_or(DEFAULT_ASSET_PATTERN)
+        .replace("{os}", requirements.os.as_deref().unwrap_or(default_os()))
+        .replace("{arch}", requirements.arch.as_deref().unwrap_or("x64"))
+        .replace(
+            "{image_type}",
+            requirements.image_type.as_deref().unwrap_or("jdk"),
+        )
+        .replace("{ext}", ext)
+}
 
-    async fn get_all_version(
-        &self,
-        _requirements: VersionRequirements,
-    ) -> anyhow::Result<Vec<Version>> {
-        let _client = GithubClient::new();
-        todo!()
+/// Match `name` against a pattern where `*` stands for any text.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return name == pattern;
+    }
+    // prefix and suffix must not overlap, so the suffix is stripped from what is left
+    let mut rest = match name
+        .strip_prefix(first)
+        .and_then(|rest| rest.strip_suffix(last))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
     }
+    true
+}
+
+/// Read versions from tags like `sapmachine-17.0.2`, `jdk-17.0.2+8` or `jdk8u322-b06`.
+fn release_version(tag_name: &str) -> Option<JavaVersion> {
+    let start = tag_name.find(|c: char| c.is_ascii_digit())?;
+    let value = &tag_name[start..];
+    match value.split_once('u') {
+        Some((feature, update)) if feature.chars().all(|c| c.is_ascii_digit()) => {
+            format!("1.{}.0_{}", feature, update).parse().ok()
+        }
+        _ => value.parse().ok(),
+    }
+}
+
+/// A release qualifies when its tag is a java version and it has an asset matching `pattern`.
+fn matching_release<'a>(
+    release: &'a GithubRelease,
+    pattern: &str,
+    include_pre: bool,
+) -> Option<(&'a GithubRelease, &'a GithubAsset, JavaVersion)> {
+    if release.draft || (release.prerelease && !include_pre) {
+        return None;
+    }
+    let version = release_version(&release.tag_name)?;
+    let asset = release
+        .assets
+        .iter()
+        .filter(|asset| matches_pattern(pattern, &asset.name))
+        .min_by_key(|asset| asset.name.len())?;
+    Some((release, asset, version))
+}
+
+fn select_release<'a>(
+    releases: &'a [GithubRelease],
+    version_req: &VersionReq,
+    pattern: &str,
+    include_pre: bool,
+) -> Result<(&'a GithubRelease, &'a GithubAsset, JavaVersion)> {
+    releases
+        .iter()
+        .filter_map(|release| matching_release(release, pattern, include_pre))
+        .filter(|(_, _, version)| version_req.matches(version))
+        .max_by(|(_, _, v1), (_, _, v2)| v1.cmp(v2))
+        .ok_or_else(|| {
+            anyhow!(
+                "Cannot find a release matching version {} with an asset like {}",
+                version_req,
+                pattern
+            )
+        })
+}
+
+/// Checksums are published next to the package, e.g. `<package>.sha256.txt`
+/// or `<package without extension>.sha256.txt`.
+fn checksum_asset<'a>(release: &'a GithubRelease, asset: &GithubAsset) -> Option<&'a GithubAsset> {
+    let stem = asset
+        .name
+        .trim_end_matches(".tar.gz")
+        .trim_end_matches(".zip");
+    let candidates = [
+        format!("{}.sha256.txt", asset.name),
+        format!("{}.sha256", asset.name),
+        format!("{}.sha256.txt", stem),
+    ];
+    release.assets.iter().find(|a| candidates.contains(&a.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches_pattern, release_version, GithubClient};
+    use crate::{
+        checksum::sha256_file,
+        client::{
+            package_client::PackageClient,
+            test_server::{Route, TestServer},
+        },
+        config::{ClientConfig, VersionRequirements},
+        provider::Provider,
+        test_support::create_fixture_archive,
+        version::Version,
+    };
+    use std::fs::read;
+    use tempdir::TempDir;
+
+    const RELEASES: &str = include_str!("fixtures/github/sapmachine_releases.json");
+    const DOWNLOAD_PATH: &str = "/SAP/SapMachine/releases/download";
+
+    fn requirements() -> VersionRequirements {
+        VersionRequirements {
+            os: Some("linux".to_owned()),
+            arch: Some("x64".to_owned()),
+            ..VersionRequirements::default()
+        }
+    }
+
+    async fn github_server() -> TestServer {
+        let server = TestServer::start().await;
+        let releases = RELEASES.replace("https://github.com", &server.base_url);
+        server.route("/repos/SAP/SapMachine/releases", Route::ok(releases));
+        server
+    }
+
+    #[test]
+    fn matches_pattern_should_support_wildcards() {
+        let name = "sapmachine-jdk-17.0.2_linux-x64_bin.tar.gz";
+        assert!(matches_pattern("*jdk*linux*x64*.tar.gz", name));
+        assert!(matches_pattern(name, name));
+        assert!(!matches_pattern("*jre*linux*x64*.tar.gz", name));
+        assert!(!matches_pattern("*jdk*linux*x64*.zip", name));
+        assert!(!matches_pattern(
+            "*jdk*linux*x64*.tar.gz",
+            "sapmachine-jdk-17.0.2_linux-x64_bin.sha256.txt"
+        ));
+        assert!(matches_pattern(
+            "OpenJDK*-jdk_x64_linux_hotspot_*.tar.gz",
+            "OpenJDK17U-jdk_x64_linux_hotspot_17.0.2_8.tar.gz"
+        ));
+        assert!(!matches_pattern("*x", "é"));
+        assert!(!matches_pattern("ab*b", "ab"));
+        assert!(matches_pattern("*é.zip", "jdk-é.zip"));
+    }
+
+    #[test]
+    fn release_version_should_read_tag_names() {
+        let version = |tag: &str| release_version(tag).map(|v| v.to_string());
+        assert_eq!(version("sapmachine-17.0.2"), Some("17.0.2".to_owned()));
+        assert_eq!(version("jdk-17.0.2+8"), Some("17.0.2+8".to_owned()));
+        assert_eq!(version("sapmachine-18+30"), Some("18+30".to_owned()));
+        assert_eq!(version("jdk8u322-b06"), Some("8.0.322+6".to_owned()));
+        assert_eq!(version("nightly"), None);
+    }
+
+    #[tokio::test]
+    async fn get_all_version_should_list_releases_with_matching_assets() {
+        let server = github_server().await;
+        let client = GithubClient::with_base_url(&server.base_url);
+
+        let versions = client.get_all_version(requirements()).await.unwrap();
+
+        let values: Vec<String> = versions.iter().map(|v| v.value.to_string()).collect();
+        assert_eq!(values, vec!["11.0.14", "17.0.1", "17.0.2"]);
+        assert!(versions
+            .iter()
+            .all(|v| v.provider == Provider::Github && v.is_lts()));
+        assert!(server.requests()[0].contains("per_page=100"));
+    }
+
+    #[tokio::test]
+    async fn download_should_fetch_asset_and_checksum() {
+        let dir = TempDir::new("jvc-github").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let server = github_server().await;
+        let asset = "sapmachine-jdk-17.0.2_linux-x64_bin";
+        server.route(
+            &format!("{}/sapmachine-17.0.2/{}.tar.gz", DOWNLOAD_PATH, asset),
+            Route::ok(read(&archive).unwrap()),
+        );
+        server.route(
+            &format!("{}/sapmachine-17.0.2/{}.sha256.txt", DOWNLOAD_PATH, asset),
+            Route::ok(format!(
+                "{}  {}.tar.gz\n",
+                sha256_file(&archive).unwrap(),
+                asset
+            )),
+        );
+        let client = GithubClient::with_base_url(&server.base_url);
+
+        let response = client
+            .download(ClientConfig {
+                requirements: requirements(),
+                download_dir: dir.path().to_owned(),
+                base_url: server.base_url.clone(),
+                version: Version::new(17, true, Provider::Github),
+                version_req: "17".parse().unwrap(),
+                cache: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.version.to_string(), "17.0.2");
+        assert_eq!(response.package_name, format!("{}.tar.gz", asset));
+        assert!(response.checksum.verify(&response.package_name).is_ok());
+    }
+
+    #[tokio::test]
+    async fn download_should_fail_without_matching_asset() {
+        let dir = TempDir::new("jvc-github").unwrap();
+        let server = github_server().await;
+        let client = GithubClient::with_base_url(&server.base_url);
+
+        let result = client
+            .download(ClientConfig {
+                requirements: VersionRequirements {
+                    arch: Some("riscv64".to_owned()),
+                    ..requirements()
+                },
+                download_dir: dir.path().to_owned(),
+                base_url: server.base_url.clone(),
+                version: Version::new(17, true, Provider::Github),
+                version_req: "17".parse().unwrap(),
+                cache: None,
+            })
+            .await;
 
-    async fn download(&self, _config: ClientConfig) -> anyhow::Result<DownloadResponse> {
-        todo!()
+        assert!(result.is_err());
     }
 }
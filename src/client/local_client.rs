@@ -0,0 +1,35 @@
+use crate::config::{ClientConfig, VersionRequirements};
+use crate::version::Version;
+
+use super::package_client::{DownloadResponse, PackageClient};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+/// Versions of the `local` provider are only installed from archives or directories on disk.
+#[derive(Debug)]
+pub struct LocalClient;
+
+impl LocalClient {
+    pub fn new() -> Self {
+        LocalClient {}
+    }
+}
+
+#[async_trait]
+impl PackageClient for LocalClient {
+    fn get_base_url(&self) -> String {
+        "".to_owned()
+    }
+
+    async fn get_all_version(&self, _requirements: VersionRequirements) -> Result<Vec<Version>> {
+        Err(anyhow!(
+            "Provider local has no remote versions. Use install --from-file or --from-dir"
+        ))
+    }
+
+    async fn download(&self, _client_config: ClientConfig) -> Result<DownloadResponse> {
+        Err(anyhow!(
+            "Provider local cannot download versions. Use install --from-file or --from-dir"
+        ))
+    }
+}
@@ -1,4 +1,10 @@
+pub mod adoptium_client;
 pub mod adoptopenjdk_client;
 pub mod azul_client;
+pub mod disco_client;
+pub mod download;
 pub mod github_client;
+pub mod local_client;
 pub mod package_client;
+#[cfg(test)]
+mod test_server;
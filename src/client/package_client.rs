@@ -1,18 +1,28 @@
 use crate::{
-    config::{ClientConfig, VersionRequirements},
+    checksum::Checksum,
+    config::{ClientConfig, JvcConfig, VersionRequirements},
     provider::Provider,
-    version::Version,
+    version::{JavaVersion, Version},
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use std::path::PathBuf;
 
-use super::{adoptopenjdk_client::AdoptOpenJDKClient, azul_client::AzulClient};
+use super::{
+    adoptium_client::AdoptiumClient, adoptopenjdk_client::AdoptOpenJDKClient,
+    azul_client::AzulClient, disco_client::DiscoClient, github_client::GithubClient,
+    local_client::LocalClient,
+};
 
 #[derive(Debug)]
 pub struct DownloadResponse {
     pub download_path: PathBuf,
     pub package_name: String,
+    pub checksum: Checksum,
+    /// Full version of the downloaded package
+    pub version: JavaVersion,
+    /// Distribution of the package when the provider serves several of them
+    pub distribution: Option<String>,
 }
 #[async_trait]
 pub trait PackageClient: Sync + Send {
@@ -25,8 +35,29 @@ pub trait PackageClient: Sync + Send {
 
 pub fn get_client(provider: &Provider) -> Box<dyn PackageClient> {
     match provider {
+        Provider::Adoptium => Box::new(AdoptiumClient::new()),
         Provider::AdoptOpenJDK => Box::new(AdoptOpenJDKClient::new()),
         Provider::Azul => Box::new(AzulClient::new()),
+        Provider::Disco => Box::new(DiscoClient::new()),
+        Provider::Github => Box::new(GithubClient::new()),
+        Provider::Local => Box::new(LocalClient::new()),
+    }
+}
+
+/// Client for the selected provider, using the mirror from config.toml when one is set.
+pub fn get_configured_client(config: &JvcConfig) -> Box<dyn PackageClient> {
+    let provider = config.provider();
+    let base_url = match config.settings.mirror(&provider) {
+        Some(mirror) => mirror.trim_end_matches('/'),
+        None => return get_client(&provider),
+    };
+    match provider {
+        Provider::Adoptium => Box::new(AdoptiumClient::with_base_url(base_url)),
+        Provider::AdoptOpenJDK => Box::new(AdoptOpenJDKClient::with_base_url(base_url)),
+        Provider::Azul => Box::new(AzulClient::with_base_url(base_url)),
+        Provider::Disco => Box::new(DiscoClient::with_base_url(base_url)),
+        Provider::Github => Box::new(GithubClient::with_base_url(base_url)),
+        Provider::Local => Box::new(LocalClient::new()),
     }
 }
 
@@ -34,11 +65,11 @@ pub fn get_client(provider: &Provider) -> Box<dyn PackageClient> {
 mod tests {
     use crate::{
         client::{adoptopenjdk_client::AdoptOpenJDKClient, azul_client::AzulClient},
-        config::VersionRequirements,
+        config::{JvcConfig, VersionRequirements},
         provider::Provider,
     };
 
-    use super::{get_client, PackageClient};
+    use super::{get_client, get_configured_client, PackageClient};
 
     #[test]
     fn get_client_should_return_correct_value() {
@@ -77,6 +108,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_base_url_should_work_for_adoptium() {
+        let client = get_client(&Provider::Adoptium);
+        let base_url = client.get_base_url();
+        assert_eq!(base_url, "https://api.adoptium.net/v3".to_owned());
+    }
+
+    #[test]
+    fn get_configured_client_should_use_mirror() {
+        let mut config = JvcConfig {
+            java_provider: Some(Provider::Azul),
+            ..JvcConfig::default()
+        };
+        assert_eq!(
+            get_configured_client(&config).get_base_url(),
+            "https://api.azul.com/zulu/download/community/v1.0"
+        );
+
+        config
+            .settings
+            .set("mirrors.azul", "https://mirror.example.com/azul/")
+            .unwrap();
+        assert_eq!(
+            get_configured_client(&config).get_base_url(),
+            "https://mirror.example.com/azul"
+        );
+    }
+
     #[test]
     fn get_base_url_should_work_for_adoptopenjdk() {
         let client = get_client(&Provider::AdoptOpenJDK);
@@ -0,0 +1,223 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// Canned response served for a path.
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Answer `Range: bytes=N-` requests with `206 Partial Content`
+    pub accept_ranges: bool,
+    /// Close the connection after sending this many body bytes
+    pub cut_after: Option<usize>,
+    /// Keep the connection open without sending anything after `cut_after`
+    pub stall: bool,
+}
+
+impl Route {
+    pub fn ok<B: Into<Vec<u8>>>(body: B) -> Self {
+        Route {
+            status: 200,
+            headers: vec![],
+            body: body.into(),
+            accept_ranges: false,
+            cut_after: None,
+            stall: false,
+        }
+    }
+
+    pub fn redirect(location: &str) -> Self {
+        Route {
+            status: 302,
+            headers: vec![("Location".to_owned(), location.to_owned())],
+            body: vec![],
+            accept_ranges: false,
+            cut_after: None,
+            stall: false,
+        }
+    }
+
+    pub fn with_ranges(self) -> Self {
+        Route {
+            accept_ranges: true,
+            ..self
+        }
+    }
+
+    pub fn cut_after(self, bytes: usize) -> Self {
+        Route {
+            cut_after: Some(bytes),
+            ..self
+        }
+    }
+
+    pub fn stall_after(self, bytes: usize) -> Self {
+        Route {
+            stall: true,
+            ..self.cut_after(bytes)
+        }
+    }
+}
+
+type Routes = Mutex<HashMap<String, VecDeque<Route>>>;
+
+/// Minimal HTTP server replaying recorded provider responses on a local port.
+#[derive(Debug)]
+pub struct TestServer {
+    pub base_url: String,
+    routes: Arc<Routes>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+#[derive(Debug, Clone)]
+struct Request {
+    target: String,
+    range: Option<String>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let routes = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let (served, recorded) = (routes.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (served, recorded) = (served.clone(), recorded.clone());
+                tokio::spawn(async move {
+                    let _ = handle(stream, &served, &recorded).await;
+                });
+            }
+        });
+
+        TestServer {
+            base_url,
+            routes,
+            requests,
+        }
+    }
+
+    /// Serve `route` for `path`, which must not contain a query string.
+    pub fn route(&self, path: &str, route: Route) {
+        self.route_sequence(path, vec![route]);
+    }
+
+    /// Serve `routes` for consecutive requests of `path`, repeating the last one.
+    pub fn route_sequence(&self, path: &str, routes: Vec<Route>) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(path.to_owned(), routes.into());
+    }
+
+    /// Paths, including query strings, requested so far.
+    pub fn requests(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|r| r.target.clone()).collect()
+    }
+
+    /// `Range` header of every request so far.
+    pub fn ranges(&self) -> Vec<Option<String>> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|r| r.range.clone()).collect()
+    }
+}
+
+async fn handle(
+    mut stream: TcpStream,
+    routes: &Routes,
+    requests: &Mutex<Vec<Request>>,
+) -> std::io::Result<()> {
+    let mut head = vec![];
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let target = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_owned();
+    let range = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("range"))
+        .map(|(_, value)| value.trim().to_owned());
+    requests.lock().unwrap().push(Request {
+        target: target.clone(),
+        range: range.clone(),
+    });
+
+    let path = target.split('?').next().unwrap_or("/");
+    let not_found = Route {
+        status: 404,
+        headers: vec![],
+        body: b"not found".to_vec(),
+        accept_ranges: false,
+        cut_after: None,
+        stall: false,
+    };
+    let route = match routes.lock().unwrap().get_mut(path) {
+        Some(sequence) if sequence.len() > 1 => sequence.pop_front(),
+        Some(sequence) => sequence.front().cloned(),
+        None => None,
+    }
+    .unwrap_or(not_found);
+
+    let start = match (&range, route.accept_ranges) {
+        (Some(range), true) => range
+            .trim_start_matches("bytes=")
+            .trim_end_matches('-')
+            .parse::<usize>()
+            .unwrap_or(0),
+        _ => 0,
+    };
+    let (status, body) = if start > 0 {
+        (206, &route.body[start..])
+    } else {
+        (route.status, &route.body[..])
+    };
+    let mut response = format!(
+        "HTTP/1.1 {} TEST\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    if route.accept_ranges {
+        response.push_str("Accept-Ranges: bytes\r\n");
+    }
+    if start > 0 {
+        response.push_str(&format!(
+            "Content-Range: bytes {}-{}/{}\r\n",
+            start,
+            route.body.len() - 1,
+            route.body.len()
+        ));
+    }
+    for (name, value) in &route.headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    stream.write_all(response.as_bytes()).await?;
+    let sent = route.cut_after.unwrap_or(body.len()).min(body.len());
+    stream.write_all(&body[..sent]).await?;
+    if route.stall {
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    }
+    stream.shutdown().await
+}
@@ -1,6 +1,9 @@
 use std::fs::{read_link, rename};
 
-use crate::{config::JvcConfig, version::Version};
+use crate::{
+    config::JvcConfig,
+    version::{Version, VersionReq},
+};
 
 use super::{
     executor::Executor,
@@ -24,6 +27,7 @@ impl Executor for Alias {
     async fn execute(self, config: crate::config::JvcConfig) -> Result<()> {
         validate_alias_name(self.name.as_str())?;
 
+        let _lock = config.lock()?;
         create_alias(self.name.as_str(), self.to_version.as_str(), &config)
     }
 }
@@ -33,9 +37,9 @@ fn create_alias(name: &str, to_version: &str, config: &JvcConfig) -> Result<()>
         warn!("We will override your existing alias.");
         override_alias(name, to_version, &config)
     } else {
-        let version = to_version.parse::<u8>()?;
+        let version = to_version.parse::<VersionReq>()?;
         let applicable_version =
-            find_version(version, &config)?.ok_or(anyhow!("Cannot find requested version!"))?;
+            find_version(&version, &config)?.ok_or(anyhow!("Cannot find requested version!"))?;
 
         apply_alias(&config, name, applicable_version)
     }
@@ -71,8 +75,8 @@ fn apply_alias(config: &JvcConfig, name: &str, applicable_version: VersionPath)
     info!(
         "Created alias {} for version {} and provider {}",
         name.green(),
-        version.value.green(),
-        version.provider.as_str().green()
+        version.value.to_string().green(),
+        version.source_name().green()
     );
 
     Ok(())
@@ -90,9 +94,9 @@ fn override_alias(name: &str, to_version: &str, config: &JvcConfig) -> Result<()
     Ok(())
 }
 
-/// alias name should not be parsable to u8
+/// alias name should not be parsable to a version
 fn validate_alias_name(alias_name: &str) -> Result<()> {
-    if let Ok(_) = alias_name.parse::<u8>() {
+    if let Ok(_) = alias_name.parse::<VersionReq>() {
         Err(anyhow!("Alias name should not be a version number"))
     } else {
         Ok(())
@@ -100,7 +104,7 @@ fn validate_alias_name(alias_name: &str) -> Result<()> {
 }
 
 fn is_alias(version: &str) -> bool {
-    if let Ok(_) = version.parse::<u8>() {
+    if let Ok(_) = version.parse::<VersionReq>() {
         false
     } else {
         true
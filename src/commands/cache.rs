@@ -0,0 +1,59 @@
+use super::executor::Executor;
+use crate::{cache::parse_age, config::JvcConfig};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::Colorize;
+use indicatif::{HumanBytes, HumanDuration};
+use log::info;
+use std::time::SystemTime;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum Cache {
+    /// List cached packages, least recently used first
+    #[structopt(name = "list", visible_aliases = &["ls"])]
+    List,
+    /// Remove cached packages
+    Prune {
+        /// Only remove packages not used for this long, like 30d, 12h or 2w
+        #[structopt(long)]
+        older_than: Option<String>,
+    },
+}
+
+#[async_trait]
+impl Executor for Cache {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let cache = config.package_cache();
+        match self {
+            Cache::List => {
+                let now = SystemTime::now();
+                let entries = cache.entries()?;
+                for entry in &entries {
+                    println!(
+                        "{} {} {} used {} ago",
+                        &entry.checksum[..12.min(entry.checksum.len())],
+                        entry.package_name().green(),
+                        HumanBytes(entry.size),
+                        HumanDuration(now.duration_since(entry.used).unwrap_or_default())
+                    );
+                }
+                let total: u64 = entries.iter().map(|entry| entry.size).sum();
+                info!(
+                    "{} packages, {} in {:?}",
+                    entries.len(),
+                    HumanBytes(total),
+                    config.get_cache_dir()
+                );
+            }
+            Cache::Prune { older_than } => {
+                let _lock = config.lock()?;
+                let older_than = older_than.as_deref().map(parse_age).transpose()?;
+                let pruned = cache.prune(older_than)?;
+                let total: u64 = pruned.iter().map(|entry| entry.size).sum();
+                info!("Removed {} packages, {}", pruned.len(), HumanBytes(total));
+            }
+        }
+        Ok(())
+    }
+}
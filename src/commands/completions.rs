@@ -0,0 +1,237 @@
+use std::{
+    collections::BTreeSet,
+    io::{stdout, Write},
+};
+
+use super::{
+    executor::Executor,
+    list::{list_aliases_versions, list_installed_versions},
+};
+use crate::{
+    config::JvcConfig,
+    shell::{
+        detect_shell,
+        shell::{Shell, AVAILABLE_SHELLS},
+    },
+    version::Version,
+    Cli,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use structopt::{clap, StructOpt};
+
+/// Subcommands whose first argument is an installed version or alias.
+const VERSION_COMMANDS: [&str; 5] = ["alias", "default", "remove", "rm", "use"];
+
+#[derive(Debug, StructOpt)]
+pub struct Completions {
+    /// Shell to generate completions for. Try to detect if this option is missing.
+    #[structopt(long)]
+    #[structopt(possible_values = AVAILABLE_SHELLS)]
+    shell: Option<Box<dyn Shell>>,
+
+    /// Print installed versions and aliases, called by the completion scripts
+    #[structopt(long, hidden = true)]
+    list_versions: bool,
+}
+
+#[async_trait]
+impl Executor for Completions {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        if self.list_versions {
+            for name in version_names(&config)? {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+
+        let shell: Box<dyn Shell> = match self.shell {
+            Some(shell) => shell,
+            None => detect_shell()?,
+        };
+        let clap_shell = shell
+            .into_clap_shell()
+            .await
+            .ok_or_else(|| anyhow!("Shell completions are not supported for {:?}", shell))?;
+        let mut out = stdout();
+        out.write_all(completion_script(clap_shell)?.as_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Installed version numbers and alias names, offered when completing a version argument.
+fn version_names(config: &JvcConfig) -> Result<BTreeSet<String>> {
+    let installation_dir = config.get_installation_dir();
+    let mut names = BTreeSet::new();
+    if installation_dir.is_dir() {
+        // staging and download dirs are not versions
+        names.extend(
+            list_installed_versions(installation_dir)?
+                .iter()
+                .filter_map(|name| Version::parse_disk_name(name).ok())
+                .map(|version| version.value.to_string()),
+        );
+    }
+    names.extend(
+        list_aliases_versions(config.aliases_dir())?
+            .into_iter()
+            .map(|alias| alias.alias_name),
+    );
+    Ok(names)
+}
+
+/// Completion script generated by clap, with installed versions and aliases completed for the
+/// first argument of [`VERSION_COMMANDS`].
+fn completion_script(shell: clap::Shell) -> Result<String> {
+    let mut script = vec![];
+    Cli::clap().gen_completions_to("jvc", shell, &mut script);
+    let script = String::from_utf8(script)?;
+    let versions = "jvc completions --list-versions";
+
+    Ok(match shell {
+        clap::Shell::Bash => {
+            let script = script.replace("complete -F _jvc -o bashdefault -o default jvc", "");
+            format!(
+                r#"{}_jvc_versions() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    if [[ ${{COMP_CWORD}} -eq 2 && ${{cur}} != -* ]]; then
+        case "${{COMP_WORDS[1]}}" in
+            {})
+                COMPREPLY=( $(compgen -W "$({} 2>/dev/null)" -- "${{cur}}") )
+                return 0
+                ;;
+        esac
+    fi
+    _jvc "$@"
+}}
+
+complete -F _jvc_versions -o bashdefault -o default jvc
+"#,
+                script.trim_end_matches('\n').to_owned() + "\n\n",
+                VERSION_COMMANDS.join("|"),
+                versions
+            )
+        }
+        clap::Shell::Zsh => {
+            let mut lines = vec![];
+            let mut in_version_command = false;
+            for line in script.lines() {
+                if line.starts_with('(') && line.ends_with(')') {
+                    let command = &line[1..line.len() - 1];
+                    in_version_command = VERSION_COMMANDS.contains(&command);
+                } else if in_version_command && line.starts_with("':") {
+                    // only the first argument is a version, `jvc alias` also takes a name
+                    in_version_command = false;
+                    lines.push(line.replace(":_files' \\", ":_jvc_versions' \\"));
+                    continue;
+                }
+                lines.push(line.to_owned());
+            }
+            let main = lines
+                .iter()
+                .rposition(|line| line == "_jvc \"$@\"")
+                .ok_or_else(|| anyhow!("Unexpected zsh completion script"))?;
+            lines.insert(
+                main,
+                format!(
+                    "_jvc_versions() {{\n    compadd -- ${{(f)\"$({} 2>/dev/null)\"}}\n}}\n",
+                    versions
+                ),
+            );
+            lines.join("\n") + "\n"
+        }
+        clap::Shell::Fish => format!(
+            "{}complete -c jvc -n \"__fish_seen_subcommand_from {}\" -f -a \"({} 2>/dev/null)\"\n",
+            script,
+            VERSION_COMMANDS.join(" "),
+            versions
+        ),
+        clap::Shell::PowerShell => insert_after_commands(&script, |command| {
+            (
+                format!("        'jvc;{}' {{", command),
+                format!(
+                    "            {} 2>$null | ForEach-Object {{ [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_) }}",
+                    versions
+                ),
+            )
+        }),
+        clap::Shell::Elvish => insert_after_commands(&script, |command| {
+            (
+                format!("        &'jvc;{}'= {{", command),
+                format!("            {} 2>/dev/null | from-lines", versions),
+            )
+        }),
+    })
+}
+
+/// Add a line after the block header of each of [`VERSION_COMMANDS`], as given by `line_for`.
+fn insert_after_commands<F>(script: &str, line_for: F) -> String
+where
+    F: Fn(&str) -> (String, String),
+{
+    let mut lines = vec![];
+    for line in script.lines() {
+        lines.push(line.to_owned());
+        for command in VERSION_COMMANDS.iter() {
+            let (header, added) = line_for(command);
+            if line == header {
+                lines.push(added);
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{completion_script, version_names};
+    use crate::config::JvcConfig;
+    use std::fs::create_dir_all;
+    use structopt::clap;
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    #[test]
+    fn version_names_should_list_installed_versions_and_aliases() {
+        let dir = TempDir::new("jvc-completions").unwrap();
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+        let installation_dir = config.get_installation_dir();
+        create_dir_all(installation_dir.join("17.0.2-lts-adoptium")).unwrap();
+        create_dir_all(installation_dir.join("11.0.14-lts-azul")).unwrap();
+        create_dir_all(installation_dir.join(".install-17")).unwrap();
+        symlink_dir(
+            installation_dir.join("17.0.2-lts-adoptium"),
+            config.aliases_dir().join("lts"),
+        )
+        .unwrap();
+
+        let names: Vec<String> = version_names(&config).unwrap().into_iter().collect();
+
+        assert_eq!(names, vec!["11.0.14", "17.0.2", "lts"]);
+    }
+
+    #[test]
+    fn completion_scripts_should_complete_versions() {
+        for shell in [
+            clap::Shell::Bash,
+            clap::Shell::Zsh,
+            clap::Shell::Fish,
+            clap::Shell::PowerShell,
+            clap::Shell::Elvish,
+        ] {
+            let script = completion_script(shell).unwrap();
+            assert!(
+                script.contains("jvc completions --list-versions"),
+                "{:?}",
+                shell
+            );
+        }
+
+        let zsh = completion_script(clap::Shell::Zsh).unwrap();
+        assert_eq!(zsh.matches(":_jvc_versions' \\").count(), 5);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{fs::rename, path::Path};
+
+use super::executor::Executor;
+use crate::{config::JvcConfig, settings::Settings};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use log::{info, warn};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum Config {
+    /// Print the value of a key, like provider, log_level, requirements.arch or mirrors.adoptium
+    Get { key: String },
+    /// Store a value in config.toml
+    Set { key: String, value: String },
+    /// Remove a value from config.toml
+    Unset { key: String },
+    /// Print all values from config.toml
+    #[structopt(name = "list", visible_aliases = &["ls"])]
+    List,
+}
+
+#[async_trait]
+impl Executor for Config {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let mut settings = config.settings.clone();
+        match self {
+            Config::Get { key } => {
+                let value = settings
+                    .get(&key)?
+                    .ok_or_else(|| anyhow!("{} is not set", key))?;
+                println!("{}", value);
+            }
+            Config::Set { key, value } => {
+                settings.set(&key, &value)?;
+                backup_unparsable(&config.settings_file())?;
+                settings.save(config.settings_file())?;
+                info!("Set {} to {}", key, value.green());
+            }
+            Config::Unset { key } => {
+                settings.unset(&key)?;
+                backup_unparsable(&config.settings_file())?;
+                settings.save(config.settings_file())?;
+                info!("Unset {}", key);
+            }
+            Config::List => {
+                for (key, value) in settings.entries() {
+                    println!("{} = {}", key, value.green());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keep a config.toml that cannot be parsed at all, saving replaces it with the values set since.
+fn backup_unparsable(path: &Path) -> Result<()> {
+    if Settings::load_unchecked(path).is_err() {
+        let backup = path.with_extension("toml.bak");
+        rename(path, &backup)?;
+        warn!("Moved unreadable {:?} to {:?}", path, backup);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::{commands::executor::Executor, config::JvcConfig};
+    use std::fs::{create_dir_all, read_to_string, write};
+    use tempdir::TempDir;
+
+    #[tokio::test]
+    async fn set_should_repair_invalid_config_file() {
+        let dir = TempDir::new("jvc-config").unwrap();
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+        create_dir_all(dir.path().join("jvc")).unwrap();
+        write(
+            config.settings_file(),
+            "provider = \"unknown\"\nlog_level = \"debug\"\n",
+        )
+        .unwrap();
+        assert!(config.clone().with_settings().is_err());
+
+        let (lenient, error) = config.clone().with_settings_lenient();
+        assert!(error.is_some());
+        Config::Set {
+            key: "provider".to_owned(),
+            value: "azul".to_owned(),
+        }
+        .execute(lenient)
+        .await
+        .unwrap();
+
+        let repaired = config.clone().with_settings().unwrap();
+        assert_eq!(
+            repaired.settings.get("provider").unwrap().as_deref(),
+            Some("azul")
+        );
+        assert_eq!(
+            repaired.settings.get("log_level").unwrap().as_deref(),
+            Some("debug")
+        );
+
+        write(config.settings_file(), "provider = [\n").unwrap();
+        let (lenient, error) = config.clone().with_settings_lenient();
+        assert!(error.is_some());
+        Config::Unset {
+            key: "provider".to_owned(),
+        }
+        .execute(lenient)
+        .await
+        .unwrap();
+        assert!(config.clone().with_settings().is_ok());
+        assert_eq!(
+            read_to_string(config.settings_file().with_extension("toml.bak")).unwrap(),
+            "provider = [\n"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    env,
+    fs::{canonicalize, read_link},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    exec::java_home,
+    executor::Executor,
+    list::follow_links,
+    use_version::{current_shell_path, resolve_project_version},
+};
+use crate::{config::JvcConfig, project::find_project_version, version::Version};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use log::warn;
+use serde::Serialize;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct Current {
+    /// Print the version as json
+    #[structopt(long)]
+    pub json: bool,
+}
+
+/// Version used by the current shell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CurrentVersion {
+    pub provider: String,
+    pub version: String,
+    pub lts: bool,
+    /// Resolved `JAVA_HOME` of the version
+    pub path: PathBuf,
+    pub source: Source,
+}
+
+/// Why the shell uses a version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Source {
+    Default,
+    Alias {
+        name: String,
+    },
+    Project {
+        file: PathBuf,
+    },
+    /// The shell link points to the version itself
+    Shell,
+}
+
+#[async_trait]
+impl Executor for Current {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let current = current_version(&current_shell_path()?, &env::current_dir()?, &config)?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&current)?);
+            return Ok(());
+        }
+
+        let source = match &current.source {
+            Source::Default => "default".to_owned(),
+            Source::Alias { name } => format!("alias {}", name),
+            Source::Project { file } => file.to_string_lossy().into_owned(),
+            Source::Shell => "shell".to_owned(),
+        };
+        println!(
+            "{} - {} ({})",
+            current.version.green(),
+            current.provider,
+            source.blue()
+        );
+        println!("{}", current.path.to_string_lossy());
+        Ok(())
+    }
+}
+
+/// Follow the shell link to the installed version and find out how it was selected.
+pub fn current_version(
+    shell_path: &Path,
+    cwd: &Path,
+    config: &JvcConfig,
+) -> Result<CurrentVersion> {
+    let target = read_link(shell_path).map_err(|_| {
+        anyhow!(
+            "{:?} is not a jvc shell link. Please evaluate `jvc env` again.",
+            shell_path
+        )
+    })?;
+    let version_dir = follow_links(&target)?;
+
+    let installation_dir = canonicalize(config.get_installation_dir())?;
+    if version_dir
+        .parent()
+        .map(canonicalize)
+        .transpose()?
+        .as_deref()
+        != Some(installation_dir.as_path())
+    {
+        return Err(anyhow!(
+            "{:?} does not point to an installed version",
+            shell_path
+        ));
+    }
+    let name = version_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let version = Version::parse_disk_name(&name)?;
+
+    Ok(CurrentVersion {
+        provider: version.source_name(),
+        version: version.value.to_string(),
+        lts: version.is_lts(),
+        path: java_home(&version_dir)?,
+        source: source(&target, cwd, config)?,
+    })
+}
+
+fn source(target: &Path, cwd: &Path, config: &JvcConfig) -> Result<Source> {
+    // a broken project file must not hide the version the shell uses
+    match project_source(target, cwd, config) {
+        Ok(Some(source)) => return Ok(source),
+        Ok(None) => {}
+        Err(e) => warn!("Ignore project version: {:#}", e),
+    }
+    if target == config.default_version_dir() {
+        return Ok(Source::Default);
+    }
+    if target.parent() == Some(config.aliases_dir().as_path()) {
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return Ok(Source::Alias { name });
+    }
+    Ok(Source::Shell)
+}
+
+fn project_source(target: &Path, cwd: &Path, config: &JvcConfig) -> Result<Option<Source>> {
+    if let Some(project) = find_project_version(cwd)? {
+        if resolve_project_version(&project, config)?.as_deref() == Some(target) {
+            return Ok(Some(Source::Project { file: project.file }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_version, Source};
+    use crate::config::JvcConfig;
+    use std::fs::{create_dir_all, write};
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    fn config(dir: &TempDir) -> JvcConfig {
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+        let installation = config
+            .get_installation_dir()
+            .join("17.0.2-lts-adoptium")
+            .join("installation");
+        create_dir_all(installation.join("bin")).unwrap();
+        write(installation.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        symlink_dir(
+            config.get_installation_dir().join("17.0.2-lts-adoptium"),
+            config.aliases_dir().join("lts"),
+        )
+        .unwrap();
+        symlink_dir(
+            config.aliases_dir().join("lts"),
+            config.default_version_dir(),
+        )
+        .unwrap();
+        config
+    }
+
+    #[test]
+    fn current_should_follow_alias_chain() {
+        let dir = TempDir::new("jvc-current").unwrap();
+        let config = config(&dir);
+        let shell_path = dir.path().join("jvc_shell_1_2");
+        symlink_dir(config.default_version_dir(), &shell_path).unwrap();
+
+        let current = current_version(&shell_path, dir.path(), &config).unwrap();
+
+        assert_eq!(current.version, "17.0.2");
+        assert_eq!(current.provider, "adoptium");
+        assert!(current.lts);
+        assert!(current.path.ends_with("17.0.2-lts-adoptium/installation"));
+        assert_eq!(current.source, Source::Default);
+    }
+
+    #[test]
+    fn current_should_report_alias_and_project_source() {
+        let dir = TempDir::new("jvc-current").unwrap();
+        let config = config(&dir);
+        let shell_path = dir.path().join("jvc_shell_1_2");
+        symlink_dir(config.aliases_dir().join("lts"), &shell_path).unwrap();
+
+        let current = current_version(&shell_path, dir.path(), &config).unwrap();
+        assert_eq!(
+            current.source,
+            Source::Alias {
+                name: "lts".to_owned()
+            }
+        );
+
+        let project = dir.path().join("project");
+        create_dir_all(&project).unwrap();
+        write(project.join(".java-version"), "lts\n").unwrap();
+        let current = current_version(&shell_path, &project, &config).unwrap();
+        assert_eq!(
+            current.source,
+            Source::Project {
+                file: project.join(".java-version")
+            }
+        );
+    }
+
+    #[test]
+    fn current_should_ignore_broken_project_file() {
+        let dir = TempDir::new("jvc-current").unwrap();
+        let config = config(&dir);
+        let shell_path = dir.path().join("jvc_shell_1_2");
+        symlink_dir(config.aliases_dir().join("lts"), &shell_path).unwrap();
+        let project = dir.path().join("project");
+        create_dir_all(&project).unwrap();
+        write(project.join(".java-version"), b"\xff\xfe").unwrap();
+
+        let current = current_version(&shell_path, &project, &config).unwrap();
+
+        assert_eq!(
+            current.source,
+            Source::Alias {
+                name: "lts".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn current_should_fail_without_shell_link() {
+        let dir = TempDir::new("jvc-current").unwrap();
+        let config = config(&dir);
+
+        assert!(current_version(&dir.path().join("missing"), dir.path(), &config).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    env,
+    fs::{read_dir, read_link, remove_dir_all},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::{
+    env::shell_java_home,
+    executor::Executor,
+    install::{validate_jdk, STAGING_PREFIX},
+    list::{list_aliases_versions, list_installed_versions},
+};
+use crate::{
+    client::package_client::get_configured_client, config::JvcConfig, provider::Provider,
+    shell_links, version::Version,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use log::info;
+use structopt::StructOpt;
+use tempdir::TempDir;
+
+#[derive(Debug, StructOpt)]
+pub struct Doctor {
+    /// Remove the links created by `jvc env` for shells that are closed and leftovers of
+    /// interrupted installs
+    #[structopt(long)]
+    pub clean: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Result of one check, with the way to fix it when it did not pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub status: Status,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass<S: Into<String>>(message: S) -> Self {
+        Check {
+            status: Status::Pass,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warn<S: Into<String>, F: Into<String>>(message: S, fix: F) -> Self {
+        Check {
+            status: Status::Warn,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail<S: Into<String>, F: Into<String>>(message: S, fix: F) -> Self {
+        Check {
+            status: Status::Fail,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn print(&self) {
+        let status = match self.status {
+            Status::Pass => "[pass]".green(),
+            Status::Warn => "[warn]".yellow(),
+            Status::Fail => "[fail]".red(),
+        };
+        println!("{} {}", status, self.message);
+        if let Some(fix) = &self.fix {
+            println!("       {}", fix.blue());
+        }
+    }
+}
+
+#[async_trait]
+impl Executor for Doctor {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        if self.clean {
+            clean(&config)?;
+        }
+
+        let mut checks = vec![];
+        checks.extend(check_base_dir(&config));
+        checks.extend(check_aliases(&config)?);
+        checks.extend(check_versions(&config)?);
+        checks.push(check_staging_dirs(&config)?);
+        checks.push(check_shell_links(&config)?);
+        checks.extend(check_shell(
+            env::var_os("JVC_SHELL_PATH").map(PathBuf::from),
+            env::var_os("PATH"),
+            env::var_os("JAVA_HOME").map(PathBuf::from),
+        ));
+        checks.push(check_provider(&config).await);
+
+        for check in &checks {
+            check.print();
+        }
+        let count = |status| checks.iter().filter(|check| check.status == status).count();
+        let failed = count(Status::Fail);
+        println!(
+            "{} passed, {} warnings, {} failed",
+            count(Status::Pass),
+            count(Status::Warn),
+            failed
+        );
+        if failed > 0 {
+            return Err(anyhow!("{} checks failed", failed));
+        }
+        Ok(())
+    }
+}
+
+fn clean(config: &JvcConfig) -> Result<()> {
+    let stale = shell_links::stale_links(&config.shells_dir())?;
+    for link in &stale {
+        shell_links::remove(link)?;
+    }
+    info!("Removed {} links of closed shells", stale.len());
+
+    // a running install keeps the lock, so its staging directory is never removed
+    let _lock = config.lock()?;
+    let staging_dirs = staging_dirs(config)?;
+    for dir in &staging_dirs {
+        remove_dir_all(dir)?;
+    }
+    info!("Removed {} interrupted installs", staging_dirs.len());
+    Ok(())
+}
+
+fn check_base_dir(config: &JvcConfig) -> Vec<Check> {
+    let base_dir = config.get_base_dir_or_default();
+    let mut checks = vec![];
+    match TempDir::new_in(&base_dir, ".doctor") {
+        Ok(_) => checks.push(Check::pass(format!(
+            "Base directory {:?} is writable",
+            base_dir
+        ))),
+        Err(e) => checks.push(Check::fail(
+            format!("Base directory {:?} is not writable: {}", base_dir, e),
+            "Fix the permissions or choose another directory with --jvc-dir",
+        )),
+    }
+    for dir in &[config.get_installation_dir(), config.aliases_dir()] {
+        if !dir.is_dir() {
+            checks.push(Check::fail(
+                format!("{:?} is not a directory", dir),
+                format!("Remove {:?} and run jvc again to recreate it", dir),
+            ));
+        }
+    }
+    if read_link(config.default_version_dir()).is_err() {
+        checks.push(Check::warn(
+            "No default version is set",
+            "Run `jvc default <version>`",
+        ));
+    }
+    checks
+}
+
+fn check_aliases(config: &JvcConfig) -> Result<Vec<Check>> {
+    let aliases = list_aliases_versions(config.aliases_dir())?;
+    let invalid: Vec<Check> = aliases
+        .iter()
+        .filter(|alias| alias.is_invalid)
+        .map(|alias| {
+            Check::fail(
+                format!("Alias {} points to a missing version", alias.alias_name),
+                format!(
+                    "Run `jvc alias <version> {}` to point it to an installed version",
+                    alias.alias_name
+                ),
+            )
+        })
+        .collect();
+    if invalid.is_empty() {
+        return Ok(vec![Check::pass(format!(
+            "{} aliases point to installed versions",
+            aliases.len()
+        ))]);
+    }
+    Ok(invalid)
+}
+
+fn check_versions(config: &JvcConfig) -> Result<Vec<Check>> {
+    let installation_dir = config.get_installation_dir();
+    let names = list_installed_versions(&installation_dir)?;
+    let mut checks = vec![];
+    for name in &names {
+        let version = match Version::parse_disk_name(name) {
+            Ok(version) => version,
+            Err(_) => {
+                checks.push(Check::warn(
+                    format!(
+                        "{:?} is not a jvc version directory",
+                        installation_dir.join(name)
+                    ),
+                    "Move it out of the java-versions directory",
+                ));
+                continue;
+            }
+        };
+        if let Err(e) = validate_jdk(&installation_dir.join(name).join("installation")) {
+            checks.push(Check::fail(
+                format!("Version {} is broken: {}", name, e),
+                format!("Run `jvc remove {}` and install it again", version.value),
+            ));
+        }
+    }
+    if checks.is_empty() {
+        checks.push(Check::pass(format!(
+            "{} installed versions contain java",
+            names.len()
+        )));
+    }
+    Ok(checks)
+}
+
+fn staging_dirs(config: &JvcConfig) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    for entry in read_dir(config.get_base_dir_or_default())? {
+        let path = entry?.path();
+        let is_staging = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(STAGING_PREFIX));
+        if is_staging && path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    Ok(dirs)
+}
+
+fn check_staging_dirs(config: &JvcConfig) -> Result<Check> {
+    let dirs = staging_dirs(config)?;
+    if dirs.is_empty() {
+        return Ok(Check::pass("No interrupted installs"));
+    }
+    Ok(Check::warn(
+        format!(
+            "{} interrupted installs left in {:?}",
+            dirs.len(),
+            config.get_base_dir_or_default()
+        ),
+        "Run `jvc doctor --clean` when no install is running",
+    ))
+}
+
+fn check_shell_links(config: &JvcConfig) -> Result<Check> {
+    let stale = shell_links::stale_links(&config.shells_dir())?;
+    if stale.is_empty() {
+        return Ok(Check::pass("No links of closed shells"));
+    }
+    Ok(Check::warn(
+        format!("{} links of closed shells", stale.len()),
+        "Run `jvc doctor --clean`",
+    ))
+}
+
+/// Check that the current shell evaluated `jvc env` and finds java through it.
+fn check_shell(
+    shell_path: Option<PathBuf>,
+    path: Option<std::ffi::OsString>,
+    java_home: Option<PathBuf>,
+) -> Vec<Check> {
+    let shell_path = match shell_path {
+        Some(shell_path) => shell_path,
+        None => return vec![Check::fail("JVC_SHELL_PATH is not set", env_fix())],
+    };
+    if !shell_path.exists() {
+        return vec![Check::fail(
+            format!(
+                "Shell link {:?} is missing or points to a missing version",
+                shell_path
+            ),
+            "Open a new shell, or run `jvc default <version>` when the default version was removed",
+        )];
+    }
+
+    let jvc_home = shell_java_home(&shell_path);
+    let paths: Vec<PathBuf> = path
+        .map(|path| env::split_paths(&path).collect())
+        .unwrap_or_default();
+    let mut checks = vec![check_path_order(&paths, &jvc_home.join("bin"))];
+    match java_home {
+        Some(java_home) if java_home == jvc_home => {
+            checks.push(Check::pass("JAVA_HOME points to the jvc version"))
+        }
+        Some(java_home) => checks.push(Check::warn(
+            format!(
+                "JAVA_HOME points to {:?} instead of {:?}",
+                java_home, jvc_home
+            ),
+            "Remove JAVA_HOME from your shell profile, `jvc env` sets it",
+        )),
+        None => checks.push(Check::warn("JAVA_HOME is not set", env_fix())),
+    }
+    checks
+}
+
+/// java found on PATH before the jvc entry is used instead of the selected version.
+fn check_path_order(paths: &[PathBuf], jvc_bin: &Path) -> Check {
+    let position = match paths.iter().position(|path| path == jvc_bin) {
+        Some(position) => position,
+        None => return Check::fail(format!("PATH does not contain {:?}", jvc_bin), env_fix()),
+    };
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let shadowing: Vec<String> = paths[..position]
+        .iter()
+        .filter(|path| path.join(java).is_file())
+        .map(|path| format!("{:?}", path))
+        .collect();
+    if shadowing.is_empty() {
+        return Check::pass("PATH uses java from jvc");
+    }
+    Check::warn(
+        format!(
+            "java from {} comes before jvc on PATH",
+            shadowing.join(", ")
+        ),
+        "Evaluate `jvc env` after other changes to PATH in your shell profile",
+    )
+}
+
+fn env_fix() -> &'static str {
+    if cfg!(windows) {
+        "Run `jvc setup` and open a new terminal"
+    } else {
+        "Add `eval \"$(jvc env)\"` to your shell profile and open a new shell"
+    }
+}
+
+async fn check_provider(config: &JvcConfig) -> Check {
+    let provider = config.provider();
+    if provider == Provider::Local {
+        return Check::pass("Local provider does not need network");
+    }
+    let url = get_configured_client(config).get_base_url();
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return Check::fail(
+                format!("Cannot create http client: {}", e),
+                "Check the proxy settings",
+            )
+        }
+    };
+    // any response means the API is reachable, even an error for the bare base url
+    match client.get(&url).send().await {
+        Ok(_) => Check::pass(format!("{} API at {} is reachable", provider.as_str(), url)),
+        Err(e) => Check::warn(
+            format!("Cannot reach {} API at {}: {}", provider.as_str(), url, e),
+            format!(
+                "Check your network and proxy settings, or set a mirror with `jvc config set mirrors.{} <url>`",
+                provider.as_str()
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_aliases, check_path_order, check_versions, Status};
+    use crate::{config::JvcConfig, test_support::fixture_java};
+    use std::{
+        fs::{create_dir_all, write},
+        path::PathBuf,
+    };
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    #[test]
+    fn broken_versions_and_dangling_aliases_should_fail() {
+        let dir = TempDir::new("jvc-doctor").unwrap();
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+        let installation_dir = config.get_installation_dir();
+        let valid = installation_dir
+            .join("17.0.2-lts-adoptium")
+            .join("installation");
+        create_dir_all(valid.join("bin")).unwrap();
+        write(valid.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        write(
+            installation_dir
+                .join("17.0.2-lts-adoptium")
+                .join(fixture_java("installation")),
+            "",
+        )
+        .unwrap();
+        let broken = installation_dir
+            .join("11.0.14-lts-azul")
+            .join("installation");
+        create_dir_all(&broken).unwrap();
+        write(broken.join("release"), "JAVA_VERSION=\"11.0.14\"\n").unwrap();
+        symlink_dir(
+            installation_dir.join("8-lts-azul"),
+            config.aliases_dir().join("old"),
+        )
+        .unwrap();
+
+        let versions = check_versions(&config).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].status, Status::Fail);
+        assert!(versions[0].message.contains("11.0.14-lts-azul"));
+
+        let aliases = check_aliases(&config).unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].status, Status::Fail);
+        assert!(aliases[0].message.contains("old"));
+    }
+
+    #[test]
+    fn java_before_jvc_on_path_should_warn() {
+        let dir = TempDir::new("jvc-doctor").unwrap();
+        let system_bin = dir.path().join("usr").join("bin");
+        create_dir_all(&system_bin).unwrap();
+        write(dir.path().join(fixture_java("usr")), "").unwrap();
+        let jvc_bin = PathBuf::from("/tmp/jvc_shell_1_2/installation/bin");
+
+        let first = check_path_order(&[jvc_bin.clone(), system_bin.clone()], &jvc_bin);
+        assert_eq!(first.status, Status::Pass);
+
+        let shadowed = check_path_order(&[system_bin.clone(), jvc_bin.clone()], &jvc_bin);
+        assert_eq!(shadowed.status, Status::Warn);
+
+        let missing = check_path_order(&[system_bin], &jvc_bin);
+        assert_eq!(missing.status, Status::Fail);
+    }
+}
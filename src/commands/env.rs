@@ -1,17 +1,26 @@
-use std::{env::temp_dir, path::PathBuf, process};
+use std::{
+    env::temp_dir,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process,
+};
 
 use crate::{
     config::JvcConfig,
+    release::find_java_home,
     shell::{
         detect_shell,
         shell::{Shell, AVAILABLE_SHELLS},
+        shell_pid,
     },
+    shell_links,
 };
 
 use super::executor::Executor;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::Utc;
+use log::debug;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -20,41 +29,63 @@ pub struct Env {
     #[structopt(long)]
     #[structopt(possible_values = AVAILABLE_SHELLS)]
     shell: Option<Box<dyn Shell>>,
+
+    /// Print a script that switches java version on directory change based on
+    /// .jvcrc, .java-version or .sdkmanrc files
+    #[structopt(long)]
+    use_on_cd: bool,
 }
 
 #[async_trait]
 impl Executor for Env {
     async fn execute(self, config: JvcConfig) -> Result<()> {
-        let shell: Box<dyn Shell> = self.shell.or_else(&detect_shell).ok_or(anyhow!(
-            "Cannot detect your shell. Please provide your shell as option."
-        ))?;
+        let shell: Box<dyn Shell> = match self.shell {
+            Some(shell) => shell,
+            None => detect_shell()?,
+        };
         let shell_path = make_symlink(&config);
-        let bin_path = shell_path.join("installation").join("bin");
+        track_shell_link(&config, &shell_path);
+        let java_home = shell_java_home(&shell_path);
+        let bin_path = java_home.join("bin");
 
         let env_path = shell.export_path(&bin_path).await;
         println!("{}", env_path);
 
-        let shell_path_as_str = shell_path
-            .to_str()
-            .ok_or(anyhow!("Cannot set shell path."))?;
         let base_dir = config.get_base_dir_or_default();
-        let jvc_dir_as_str = base_dir
-            .to_str()
-            .ok_or(anyhow!("Cannot read base directory."))?;
-
-        let jvc_shell_path = shell.set_env_var("JVC_SHELL_PATH", shell_path_as_str).await;
-        let jvc_dir = shell.set_env_var("JVC_DIR", jvc_dir_as_str).await;
-        let jvc_loglevel = shell
-            .set_env_var("JVC_LOGLEVEL", config.log_level.into())
-            .await;
-        let jvc_provider = shell
-            .set_env_var("JVC_PROVIDER", &config.java_provider.as_str())
+        let jvc_shell_path = shell
+            .set_env_var("JVC_SHELL_PATH", shell_path.as_os_str())
             .await;
+        let jvc_dir = shell.set_env_var("JVC_DIR", base_dir.as_os_str()).await;
+        let java_home_var = shell.set_env_var("JAVA_HOME", java_home.as_os_str()).await;
 
         println!("{}", jvc_shell_path);
         println!("{}", jvc_dir);
-        println!("{}", jvc_loglevel);
-        println!("{}", jvc_provider);
+        println!("{}", java_home_var);
+        // only explicit values are exported, otherwise they would shadow config.toml
+        if let Some(log_level) = config.log_level {
+            println!(
+                "{}",
+                shell
+                    .set_env_var("JVC_LOGLEVEL", OsStr::new(log_level.into()))
+                    .await
+            );
+        }
+        if let Some(provider) = &config.java_provider {
+            println!(
+                "{}",
+                shell
+                    .set_env_var("JVC_PROVIDER", OsStr::new(&provider.as_str()))
+                    .await
+            );
+        }
+
+        if self.use_on_cd {
+            let use_on_cd = shell
+                .use_on_cd()
+                .await
+                .ok_or_else(|| anyhow!("Option --use-on-cd is not supported for {:?}", shell))?;
+            println!("{}", use_on_cd);
+        }
 
         Ok(())
     }
@@ -72,6 +103,43 @@ pub fn make_symlink(config: &JvcConfig) -> PathBuf {
     temp_dir
 }
 
+/// Record the link so it is removed once the shell exits, and remove the links of closed shells.
+/// Failures are only logged, they must not break the shell setup.
+fn track_shell_link(config: &JvcConfig, shell_path: &Path) {
+    let shells_dir = config.shells_dir();
+    match shell_links::stale_links(&shells_dir) {
+        Ok(stale) => {
+            for link in stale {
+                if let Err(e) = shell_links::remove(&link) {
+                    debug!("Cannot remove shell link {:?}: {}", link.path, e);
+                }
+            }
+        }
+        Err(e) => debug!("Cannot read shell links: {}", e),
+    }
+
+    match shell_pid() {
+        Some(pid) => {
+            if let Err(e) = shell_links::register(&shells_dir, pid, shell_path) {
+                debug!("Cannot track shell link {:?}: {}", shell_path, e);
+            }
+        }
+        None => debug!("Cannot find shell process, {:?} is not tracked", shell_path),
+    }
+}
+
+/// `JAVA_HOME` inside the shell symlink, so switching the symlink moves it with `PATH`.
+///
+/// Installs and imports keep the JDK root in `installation`, so the path fits every version.
+/// Versions installed by older releases may still use the macOS `Contents/Home` layout, which is
+/// only detected here when the shell starts; switching to or from them needs `jvc env` again.
+/// The path is not canonicalized, otherwise it would stay on the version selected when the shell
+/// started.
+pub fn shell_java_home(shell_path: &Path) -> PathBuf {
+    let installation = shell_path.join("installation");
+    find_java_home(&installation).unwrap_or(installation)
+}
+
 fn create_symlink_path(sys_temp_dir: &PathBuf) -> PathBuf {
     let temp_dir_name = format!(
         "jvc_shell_{}_{}",
@@ -80,3 +148,35 @@ fn create_symlink_path(sys_temp_dir: &PathBuf) -> PathBuf {
     );
     sys_temp_dir.join(temp_dir_name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::shell_java_home;
+    use std::fs::{create_dir_all, write};
+    use tempdir::TempDir;
+
+    #[test]
+    fn shell_java_home_should_point_to_installation() {
+        let dir = TempDir::new("jvc-env").unwrap();
+        let shell_path = dir.path().join("jvc_shell_1_2");
+
+        assert_eq!(
+            shell_java_home(&shell_path),
+            shell_path.join("installation")
+        );
+    }
+
+    #[test]
+    fn shell_java_home_should_use_macos_bundle_home() {
+        let dir = TempDir::new("jvc-env").unwrap();
+        let shell_path = dir.path().join("jvc_shell_1_2");
+        let home = shell_path
+            .join("installation")
+            .join("Contents")
+            .join("Home");
+        create_dir_all(&home).unwrap();
+        write(home.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+
+        assert_eq!(shell_java_home(&shell_path), home);
+    }
+}
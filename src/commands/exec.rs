@@ -0,0 +1,160 @@
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use super::{executor::Executor, list::find_version_or_alias};
+use crate::{config::JvcConfig, release::find_java_home};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::debug;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct Exec {
+    /// Installed version or alias used to run the command, like 17, >=11 or default
+    #[structopt(long = "version", short = "v")]
+    pub version: String,
+
+    /// Command to run and its arguments, after --
+    #[structopt(required = true, last = true, parse(from_os_str))]
+    pub command: Vec<OsString>,
+}
+
+#[async_trait]
+impl Executor for Exec {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let version_dir = find_version_or_alias(&self.version, &config)?
+            .ok_or_else(|| anyhow!("Cannot find installed version or alias {}", self.version))?;
+        let java_home = java_home(&version_dir)?;
+        debug!("Run {:?} with JAVA_HOME {:?}", self.command, java_home);
+
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| anyhow!("Please specify a command to run"))?;
+        let mut command = Command::new(program);
+        command.args(args);
+        set_java_env(&mut command, &java_home, env::var_os("PATH"))?;
+        run(command)
+    }
+}
+
+/// JDK root of an installed version, resolved once so a later alias change does not affect the child.
+pub fn java_home(version_dir: &Path) -> Result<PathBuf> {
+    let installation = resolved_installation(version_dir)?;
+    Ok(find_java_home(&installation).unwrap_or(installation))
+}
+
+#[cfg(not(windows))]
+fn resolved_installation(version_dir: &Path) -> Result<PathBuf> {
+    Ok(std::fs::canonicalize(version_dir.join("installation"))?)
+}
+
+/// `canonicalize` returns verbatim paths like `\\?\C:\` on Windows, which `.cmd` launchers such
+/// as `mvn.cmd` reject, so only the alias and installation links are followed.
+#[cfg(windows)]
+fn resolved_installation(version_dir: &Path) -> Result<PathBuf> {
+    use super::list::follow_links;
+    follow_links(&follow_links(version_dir)?.join("installation"))
+}
+
+/// Point `JAVA_HOME` to `java_home` and put its `bin` first on `PATH`, for the child only.
+fn set_java_env(command: &mut Command, java_home: &Path, path: Option<OsString>) -> Result<()> {
+    let mut paths = vec![java_home.join("bin")];
+    if let Some(path) = path {
+        paths.extend(env::split_paths(&path));
+    }
+    command
+        .env("JAVA_HOME", java_home)
+        .env("PATH", env::join_paths(paths)?);
+    Ok(())
+}
+
+/// Replace jvc with the command, so it receives signals directly and its exit code is kept.
+#[cfg(unix)]
+fn run(mut command: Command) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    let error = command.exec();
+    Err(anyhow!("Cannot run {:?}: {}", command.get_program(), error))
+}
+
+/// Ctrl+C reaches every process of the console, so waiting and exiting with the same code is enough.
+#[cfg(windows)]
+fn run(mut command: Command) -> Result<()> {
+    let status = command
+        .status()
+        .map_err(|e| anyhow!("Cannot run {:?}: {}", command.get_program(), e))?;
+    std::process::exit(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{java_home, set_java_env};
+    use std::{
+        env,
+        ffi::OsString,
+        fs::{create_dir_all, write},
+        path::PathBuf,
+        process::Command,
+    };
+    use tempdir::TempDir;
+
+    #[test]
+    fn java_home_should_use_macos_bundle_home() {
+        let dir = TempDir::new("jvc-exec").unwrap();
+        let home = dir
+            .path()
+            .join("17.0.2-lts-adoptium")
+            .join("installation")
+            .join("Contents")
+            .join("Home");
+        create_dir_all(&home).unwrap();
+        write(home.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+
+        let java_home = java_home(&dir.path().join("17.0.2-lts-adoptium")).unwrap();
+
+        assert!(java_home.ends_with("installation/Contents/Home"));
+    }
+
+    #[test]
+    fn set_java_env_should_prepend_bin_to_path() {
+        let java_home = PathBuf::from("/jvc/java-versions/17/installation");
+        let path = env::join_paths(vec![PathBuf::from("/usr/bin"), PathBuf::from("/bin")]).unwrap();
+        let mut command = Command::new("java");
+
+        set_java_env(&mut command, &java_home, Some(path)).unwrap();
+
+        let envs: Vec<(OsString, Option<OsString>)> = command
+            .get_envs()
+            .map(|(k, v)| (k.to_owned(), v.map(|v| v.to_owned())))
+            .collect();
+        assert!(envs.contains(&(
+            OsString::from("JAVA_HOME"),
+            Some(java_home.clone().into_os_string())
+        )));
+        let (_, path) = envs.iter().find(|(k, _)| k == "PATH").unwrap();
+        let paths: Vec<PathBuf> = env::split_paths(path.as_ref().unwrap()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                java_home.join("bin"),
+                PathBuf::from("/usr/bin"),
+                PathBuf::from("/bin")
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn child_should_see_java_home_and_keep_exit_code() {
+        let java_home = PathBuf::from("/opt/jdk-17");
+        let mut command = Command::new("sh");
+        command.args(["-c", "test \"$JAVA_HOME\" = /opt/jdk-17 && exit 3"]);
+        set_java_env(&mut command, &java_home, env::var_os("PATH")).unwrap();
+
+        assert_eq!(command.status().unwrap().code(), Some(3));
+    }
+}
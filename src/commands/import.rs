@@ -0,0 +1,208 @@
+use std::{
+    fs::{canonicalize, create_dir_all, read_dir, symlink_metadata},
+    path::{Path, PathBuf},
+};
+
+use super::executor::Executor;
+use crate::{
+    config::JvcConfig,
+    release::{find_java_home, JdkRelease},
+    version::{is_lts_feature, Version},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use colored::Colorize;
+use dirs::home_dir;
+use log::{debug, info, warn};
+use structopt::StructOpt;
+use symlink::symlink_dir;
+
+#[derive(Debug, StructOpt)]
+pub struct Import {
+    /// Additional directories containing JDKs to scan
+    #[structopt(parse(from_os_str))]
+    pub paths: Vec<PathBuf>,
+
+    /// Only show the JDKs that would be imported
+    #[structopt(long)]
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Executor for Import {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let _lock = config.lock()?;
+        let mut locations = well_known_locations();
+        locations.extend(self.paths);
+
+        let imported = import_from(&locations, &config, self.dry_run)?;
+        if imported.is_empty() {
+            info!("No new JDK found");
+        }
+        Ok(())
+    }
+}
+
+/// Directories where package managers, sdkman and IDEs usually install JDKs.
+fn well_known_locations() -> Vec<PathBuf> {
+    let mut locations = vec![
+        PathBuf::from("/usr/lib/jvm"),
+        PathBuf::from("/Library/Java/JavaVirtualMachines"),
+    ];
+    if let Some(home) = home_dir() {
+        locations.push(home.join(".sdkman").join("candidates").join("java"));
+        locations.push(home.join(".jdks"));
+        locations.push(
+            home.join("Library")
+                .join("Java")
+                .join("JavaVirtualMachines"),
+        );
+    }
+    locations
+}
+
+/// Register every JDK found directly under `locations` and return the new versions.
+pub fn import_from(
+    locations: &[PathBuf],
+    config: &JvcConfig,
+    dry_run: bool,
+) -> Result<Vec<Version>> {
+    let installation_dir = config.get_installation_dir();
+    let managed_dir = canonicalize(&installation_dir)?;
+    let mut imported = vec![];
+    for location in locations.iter().filter(|location| location.is_dir()) {
+        debug!("Scan {:?} for JDKs", location);
+        for entry in read_dir(location)? {
+            let path = entry?.path();
+            // links like `default-java` or sdkman `current` point to JDKs found anyway
+            if symlink_metadata(&path)?.file_type().is_symlink() || !path.is_dir() {
+                continue;
+            }
+            let java_home = match find_java_home(&path) {
+                Some(java_home) => canonicalize(java_home)?,
+                None => continue,
+            };
+            if java_home.starts_with(&managed_dir) {
+                continue;
+            }
+            let release = match JdkRelease::read(&java_home) {
+                Ok(release) => release,
+                Err(e) => {
+                    warn!("Skip {:?}: {}", java_home, e);
+                    continue;
+                }
+            };
+
+            let (provider, distribution) = release.provider();
+            let is_lts = is_lts_feature(release.version.feature);
+            let version = Version::from_java_version(release.version, is_lts, provider)
+                .with_distribution(distribution);
+            let version_dir = installation_dir.join(version.as_disk_name());
+            if version_dir.exists() {
+                debug!("{:?} is already registered as {:?}", java_home, version_dir);
+                continue;
+            }
+
+            info!(
+                "Import java {} from {}",
+                version.to_string().green(),
+                java_home.to_string_lossy()
+            );
+            if !dry_run {
+                create_dir_all(&version_dir)?;
+                symlink_dir(&java_home, version_dir.join("installation"))?;
+            }
+            imported.push(version);
+        }
+    }
+    Ok(imported)
+}
+
+/// Imported versions link their `installation` to a JDK managed outside jvc.
+pub fn is_external<P: AsRef<Path>>(version_dir: P) -> bool {
+    symlink_metadata(version_dir.as_ref().join("installation"))
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import_from, is_external};
+    use crate::config::JvcConfig;
+    use std::{
+        fs::{create_dir_all, write},
+        path::Path,
+    };
+    use tempdir::TempDir;
+
+    fn create_jdk(path: &Path, implementor: &str, version: &str) {
+        create_dir_all(path.join("bin")).unwrap();
+        write(
+            path.join("release"),
+            format!(
+                "IMPLEMENTOR=\"{}\"\nJAVA_VERSION=\"{}\"\n",
+                implementor, version
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn import_should_link_found_jdks() {
+        let dir = TempDir::new("jvc-import").unwrap();
+        let location = dir.path().join("jvm");
+        create_jdk(
+            &location.join("java-17-openjdk"),
+            "Eclipse Adoptium",
+            "17.0.2",
+        );
+        create_jdk(
+            &location.join("zulu-11.jdk").join("Contents").join("Home"),
+            "Azul Systems, Inc.",
+            "11.0.14",
+        );
+        create_dir_all(location.join("not-a-jdk")).unwrap();
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+
+        let locations = vec![location];
+        let imported = import_from(&locations, &config, false).unwrap();
+
+        let mut names: Vec<String> = imported.iter().map(|v| v.as_disk_name()).collect();
+        names.sort();
+        assert_eq!(names, vec!["11.0.14-lts-azul", "17.0.2-lts-adoptium"]);
+        let version_dir = config.get_installation_dir().join("17.0.2-lts-adoptium");
+        assert!(is_external(&version_dir));
+        assert!(version_dir.join("installation").join("bin").is_dir());
+        assert!(config
+            .get_installation_dir()
+            .join("11.0.14-lts-azul")
+            .join("installation")
+            .join("release")
+            .is_file());
+
+        let imported_again = import_from(&locations, &config, false).unwrap();
+        assert!(imported_again.is_empty());
+    }
+
+    #[test]
+    fn dry_run_should_not_register_versions() {
+        let dir = TempDir::new("jvc-import").unwrap();
+        let location = dir.path().join("jvm");
+        create_jdk(&location.join("jdk-17"), "Eclipse Adoptium", "17.0.2");
+        let config = JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        };
+
+        let imported = import_from(&[location], &config, true).unwrap();
+
+        assert_eq!(imported.len(), 1);
+        assert!(!config
+            .get_installation_dir()
+            .join("17.0.2-lts-adoptium")
+            .exists());
+    }
+}
@@ -1,72 +1,352 @@
-use std::{fs::rename, path::PathBuf};
+use std::{
+    ffi::OsStr,
+    fs::{copy, create_dir_all, read_dir, rename},
+    path::{Path, PathBuf},
+};
 
 use super::executor::Executor;
 use crate::{
     archive::unarchive,
-    client::package_client::get_client,
-    config::{new_client_config, VersionRequirements},
+    client::package_client::{get_configured_client, DownloadResponse},
+    config::{new_client_config, ClientConfig, JvcConfig, VersionRequirements},
     provider::Provider,
-    version::Version,
+    release::{find_java_home, JdkRelease},
+    version::{is_lts_feature, Version, VersionReq},
 };
 use anyhow::anyhow;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use colored::*;
-use log::{debug, warn};
+use log::{debug, info, warn};
 use structopt::StructOpt;
+use tempdir::TempDir;
 #[derive(Debug, StructOpt)]
 pub struct Install {
-    /// Version to install
-    pub version: u32,
+    /// Version to install: a feature version like 17, a full version like 11.0.12+7 or a range like >=17
+    ///
+    /// Optional with --from-file or --from-dir, where it is checked against the detected version
+    #[structopt(required_unless_one = &["from-file", "from-dir"])]
+    pub version: Option<VersionReq>,
 
     #[structopt(flatten)]
     pub requirements: VersionRequirements,
+
+    /// Install the package even if its SHA-256 checksum cannot be verified
+    #[structopt(long)]
+    pub skip_checksum: bool,
+
+    /// Install from a local .tar.gz or .zip JDK archive instead of downloading it
+    #[structopt(long, parse(from_os_str), conflicts_with = "from-dir")]
+    pub from_file: Option<PathBuf>,
+
+    /// Install a copy of an unpacked JDK directory
+    #[structopt(long, parse(from_os_str))]
+    pub from_dir: Option<PathBuf>,
 }
 
 #[async_trait]
 impl Executor for Install {
-    async fn execute(self, config: crate::config::JvcConfig) -> Result<()> {
-        let provider: &Provider = &config.java_provider;
-        let client = get_client(provider);
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let _lock = config.lock()?;
+        // downloads left by an interrupted install are never resumed
+        if let Err(e) = config.clean_up_downloads_dir() {
+            warn!("Cannot clean up downloads dir: {}", e)
+        }
 
-        let versions = client.get_all_version(self.requirements.clone()).await?;
-        let version = get_selected_version(versions, self.version).await?;
+        if let Some(file) = &self.from_file {
+            return install_from_file(file, self.version.as_ref(), &config);
+        }
+        if let Some(dir) = &self.from_dir {
+            return install_from_dir(dir, self.version.as_ref(), &config);
+        }
+        let version_req = self
+            .version
+            .ok_or_else(|| anyhow!("Please specify a version to install"))?;
 
-        let install_dir = &config.get_installation_dir().join(format!(
-            "{}-{}",
-            version.as_disk_version(),
-            provider.as_str()
-        ));
+        let config = JvcConfig {
+            java_provider: Some(config.provider().migrate()),
+            ..config
+        };
+        let provider: &Provider = &config.provider();
+        let client = get_configured_client(&config);
+        let requirements = config.requirements(self.requirements);
+
+        let versions = client.get_all_version(requirements.clone()).await?;
+        let version = get_selected_version(versions, &version_req).await?;
+        let is_lts = version.is_lts();
+        if let Some(installed) = find_installed(
+            &config,
+            &version,
+            &version_req,
+            requirements.distribution.as_deref(),
+        )? {
+            info!(
+                "Version {} is already installed",
+                installed.to_string().green()
+            );
+            return Ok(());
+        }
 
         let download_response = client
-            .download(new_client_config(&config, version, self.requirements))
+            .download(ClientConfig {
+                version_req,
+                ..new_client_config(&config, version, requirements)
+            })
             .await?;
         debug!("Downloaded package {}", download_response.package_name);
-        let archive_result = unarchive(
-            download_response.package_name.as_ref(),
-            download_response.download_path,
-            install_dir.to_owned(),
+        verify_download(&download_response, self.skip_checksum)?;
+
+        let installed_version =
+            Version::from_java_version(download_response.version.clone(), is_lts, provider.clone())
+                .with_distribution(download_response.distribution.clone());
+        let install_dir = &config
+            .get_installation_dir()
+            .join(installed_version.as_disk_name());
+        if install_dir.exists() {
+            info!(
+                "Version {} is already installed",
+                installed_version.to_string().green()
+            );
+            return Ok(());
+        }
+
+        let staging = staging_dir(&config)?;
+        let jdk_root = unpack(
+            &download_response.package_name,
+            &download_response.download_path,
+            staging.path(),
+        )?;
+        move_into_place(&jdk_root, staging.path(), install_dir)?;
+        info!(
+            "Installed java {} as {}",
+            installed_version.to_string().green(),
+            installed_version.as_disk_name()
         );
+        Ok(())
+    }
+}
 
-        let package_name = std::fs::read_dir(install_dir)?
-            .next()
-            .ok_or(anyhow!("Cannot get extracted package."))??
-            .file_name()
-            .into_string()
-            .expect("Cannot extract archive name.");
-
-        match archive_result {
-            Ok(_) => rename_package(install_dir, package_name.as_ref())?,
-            Err(e) => warn!("Cannot extract archive: {}", e),
+fn install_from_file(file: &Path, version: Option<&VersionReq>, config: &JvcConfig) -> Result<()> {
+    let package_name = file
+        .file_name()
+        .and_then(OsStr::to_str)
+        .ok_or_else(|| anyhow!("Cannot read archive name of {:?}", file))?;
+    let staging = staging_dir(config)?;
+    let jdk_root = unpack(package_name, file, staging.path())?;
+    register_local_version(&jdk_root, staging.path(), version, config)
+}
+
+fn install_from_dir(dir: &Path, version: Option<&VersionReq>, config: &JvcConfig) -> Result<()> {
+    if find_java_home(dir).is_none() {
+        return Err(anyhow!("Cannot find a JDK release file in {:?}", dir));
+    }
+    let staging = staging_dir(config)?;
+    let jdk_root = staging.path().join("jdk");
+    debug!("Copy {:?} to {:?}", dir, jdk_root);
+    copy_dir(dir, &jdk_root)?;
+    register_local_version(&jdk_root, staging.path(), version, config)
+}
+
+/// Prefix of the staging directories, left behind only when jvc is killed during an install.
+pub const STAGING_PREFIX: &str = ".install";
+
+/// Installs are prepared in a temporary directory next to `java-versions`, which is removed
+/// with everything left in it when the install fails.
+fn staging_dir(config: &JvcConfig) -> Result<TempDir> {
+    Ok(TempDir::new_in(
+        config.get_base_dir_or_default(),
+        STAGING_PREFIX,
+    )?)
+}
+
+/// Unpack `archive` into `staging` and return the directory containing the JDK.
+fn unpack(package_name: &str, archive: &Path, staging: &Path) -> Result<PathBuf> {
+    let extracted = staging.join("archive");
+    unarchive(package_name, archive, extracted.as_path())
+        .with_context(|| format!("Cannot extract {}", package_name))?;
+    match find_java_home(&extracted) {
+        Some(_) => Ok(extracted),
+        None => single_child_dir(&extracted),
+    }
+}
+
+/// Validate the staged JDK and move it to `install_dir` with a single rename,
+/// so a version directory is either complete or missing.
+///
+/// Only the JDK root of macOS bundles is kept, so `installation/bin` exists for every version
+/// and the `JAVA_HOME` exported by `jvc env` stays valid after `jvc use`.
+fn move_into_place(jdk_root: &Path, staging: &Path, install_dir: &Path) -> Result<()> {
+    validate_jdk(jdk_root)?;
+    let java_home = find_java_home(jdk_root)
+        .ok_or_else(|| anyhow!("Cannot find a JDK release file in {:?}", jdk_root))?;
+    let version_dir = staging.join("version");
+    create_dir_all(&version_dir)?;
+    rename(java_home, version_dir.join("installation"))?;
+    debug!("Move {:?} to {:?}", version_dir, install_dir);
+    rename(&version_dir, install_dir)
+        .with_context(|| format!("Cannot move installation to {:?}", install_dir))?;
+    Ok(())
+}
+
+pub fn validate_jdk(jdk_root: &Path) -> Result<()> {
+    let java_home = find_java_home(jdk_root)
+        .ok_or_else(|| anyhow!("Cannot find a JDK release file in {:?}", jdk_root))?;
+    let java = java_home
+        .join("bin")
+        .join(if cfg!(windows) { "java.exe" } else { "java" });
+    if !java.is_file() {
+        return Err(anyhow!("Package does not contain {:?}", java));
+    }
+    Ok(())
+}
+
+/// Move an unpacked JDK to `java-versions` under the name derived from its `release` file.
+fn register_local_version(
+    jdk_root: &Path,
+    staging: &Path,
+    version_req: Option<&VersionReq>,
+    config: &JvcConfig,
+) -> Result<()> {
+    let java_home = find_java_home(jdk_root)
+        .ok_or_else(|| anyhow!("Cannot find a JDK release file in {:?}", jdk_root))?;
+    let release = JdkRelease::read(java_home)?;
+    if let Some(version_req) = version_req {
+        if !version_req.matches(&release.version) {
+            return Err(anyhow!(
+                "Detected java version {} does not match requested version {}",
+                release.version,
+                version_req
+            ));
+        }
+    }
+
+    let (provider, distribution) = release.provider();
+    let is_lts = is_lts_feature(release.version.feature);
+    let version = Version::from_java_version(release.version, is_lts, provider)
+        .with_distribution(distribution);
+    let install_dir = config.get_installation_dir().join(version.as_disk_name());
+    if install_dir.exists() {
+        info!(
+            "Version {} is already installed",
+            version.to_string().green()
+        );
+        return Ok(());
+    }
+
+    move_into_place(jdk_root, staging, &install_dir)?;
+    info!(
+        "Installed java {} as {}",
+        version.to_string().green(),
+        version.as_disk_name()
+    );
+    Ok(())
+}
+
+fn single_child_dir(dir: &Path) -> Result<PathBuf> {
+    let mut dirs = read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+    match (dirs.next(), dirs.next()) {
+        (Some(child), None) => Ok(child),
+        _ => Err(anyhow!("Archive does not contain a single JDK directory")),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    create_dir_all(to)?;
+    for entry in read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            copy(entry.path(), &target)?;
         }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        copy_dir(from, to)
+    } else {
+        copy(from, to)?;
         Ok(())
     }
 }
 
-async fn get_selected_version(mut versions: Vec<Version>, version: u32) -> Result<Version> {
+fn verify_download(download_response: &DownloadResponse, skip_checksum: bool) -> Result<()> {
+    if skip_checksum {
+        warn!(
+            "Skipping checksum verification for {}",
+            download_response.package_name
+        );
+        return Ok(());
+    }
+
+    if let Err(e) = download_response
+        .checksum
+        .verify(download_response.package_name.as_ref())
+    {
+        if let Err(remove_err) = std::fs::remove_file(&download_response.download_path) {
+            debug!("Cannot remove rejected download: {}", remove_err);
+        }
+        return Err(e.into());
+    }
+    debug!(
+        "Checksum verified for {}: {}",
+        download_response.package_name, download_response.checksum.actual
+    );
+    Ok(())
+}
+
+/// Installed version of the provider that `version`, as resolved from the listing, and
+/// `version_req` select, so the package does not need to be downloaded again.
+fn find_installed(
+    config: &JvcConfig,
+    version: &Version,
+    version_req: &VersionReq,
+    distribution: Option<&str>,
+) -> Result<Option<Version>> {
+    let installation_dir = config.get_installation_dir();
+    if !installation_dir.is_dir() {
+        return Ok(None);
+    }
+    let mut installed = vec![];
+    for entry in read_dir(installation_dir)? {
+        let name = entry?.file_name();
+        if let Ok(candidate) = Version::parse_disk_name(&name.to_string_lossy()) {
+            installed.push(candidate);
+        }
+    }
+    Ok(installed
+        .into_iter()
+        .filter(|candidate| candidate.provider == version.provider)
+        .filter(|candidate| {
+            distribution.is_none() || candidate.distribution.as_deref() == distribution
+        })
+        .filter(|candidate| {
+            version.value.is_prefix_of(&candidate.value) && version_req.matches(&candidate.value)
+        })
+        .max_by(|v1, v2| v1.value.cmp(&v2.value)))
+}
+
+async fn get_selected_version(versions: Vec<Version>, version: &VersionReq) -> Result<Version> {
     let selected_version = versions
-        .drain(..)
-        .find(|v| v.value.eq_ignore_ascii_case(version.to_string().as_ref()))
+        .into_iter()
+        .filter(|v| version.allows_feature(v.value.feature))
+        .max_by(|v1, v2| v1.value.cmp(&v2.value))
         .ok_or(anyhow!(
             "Cannot find a version to match your selection {}",
             version.to_string().red()
@@ -74,19 +354,219 @@ async fn get_selected_version(mut versions: Vec<Version>, version: u32) -> Resul
     Ok(selected_version)
 }
 
-fn rename_package(install_dir: &PathBuf, package_name: &str) -> Result<()> {
-    debug!(
-        "Rename from {:?} to {:?}",
-        install_dir
-            .join(package_name.replace(".tar.gz", "").replace(".zip", ""))
-            .as_path(),
-        install_dir.join("installation").as_path()
-    );
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_installed, install_from_dir, install_from_file, move_into_place, verify_download,
+    };
+    use crate::{
+        checksum::{sha256_file, Checksum, ChecksumError},
+        client::package_client::DownloadResponse,
+        config::JvcConfig,
+        provider::Provider,
+        test_support::{create_fixture_archive, fixture_java},
+        version::Version,
+    };
+    use std::fs::{create_dir_all, read_dir, write};
+    use tempdir::TempDir;
 
-    rename(
-        install_dir.join(package_name.replace(".tar.gz", "").replace(".zip", "")),
-        install_dir.join("installation"),
-    )?;
+    fn config(dir: &TempDir) -> JvcConfig {
+        JvcConfig {
+            base_dir: Some(dir.path().join("jvc")),
+            ..JvcConfig::default()
+        }
+    }
 
-    Ok(())
+    fn download_response(dir: &TempDir, expected: Option<String>) -> DownloadResponse {
+        let download_path = create_fixture_archive(dir.path());
+        let actual = sha256_file(&download_path).unwrap();
+        DownloadResponse {
+            download_path,
+            package_name: "fixture-jdk.tar.gz".to_owned(),
+            checksum: Checksum { expected, actual },
+            version: "11.0.12+7".parse().unwrap(),
+            distribution: None,
+        }
+    }
+
+    #[test]
+    fn find_installed_should_match_resolved_version_and_requirement() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let config = config(&dir);
+        let installation_dir = config.get_installation_dir();
+        create_dir_all(installation_dir.join("17.0.2+8-lts-adoptium")).unwrap();
+        create_dir_all(installation_dir.join("17.0.1+12-lts-disco.zulu")).unwrap();
+        let find = |version: Version, req: &str, distribution: Option<&str>| {
+            find_installed(&config, &version, &req.parse().unwrap(), distribution)
+                .unwrap()
+                .map(|installed| installed.as_disk_name())
+        };
+        let adoptium_17 = || Version::new(17, true, Provider::Adoptium);
+
+        assert_eq!(
+            find(adoptium_17(), "17", None),
+            Some("17.0.2+8-lts-adoptium".to_owned())
+        );
+        assert_eq!(find(adoptium_17(), "17.0.3", None), None);
+        assert_eq!(
+            find(Version::new(11, true, Provider::Adoptium), "11", None),
+            None
+        );
+        assert_eq!(
+            find(Version::new(17, true, Provider::Azul), "17", None),
+            None
+        );
+
+        let disco_17 = || Version::new(17, true, Provider::Disco);
+        assert_eq!(
+            find(disco_17(), ">=17", Some("zulu")),
+            Some("17.0.1+12-lts-disco.zulu".to_owned())
+        );
+        assert_eq!(find(disco_17(), "17", Some("corretto")), None);
+    }
+
+    #[test]
+    fn verify_download_should_accept_matching_checksum() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let expected = sha256_file(&archive).unwrap();
+        let response = download_response(&dir, Some(expected));
+
+        assert!(verify_download(&response, false).is_ok());
+        assert!(response.download_path.exists());
+    }
+
+    #[test]
+    fn verify_download_should_reject_and_remove_tampered_archive() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let response = download_response(&dir, Some("ff".repeat(32)));
+
+        let error = verify_download(&response, false).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ChecksumError>(),
+            Some(ChecksumError::Mismatch { .. })
+        ));
+        assert!(!response.download_path.exists());
+    }
+
+    #[test]
+    fn verify_download_should_allow_skipping_checksum() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let response = download_response(&dir, Some("ff".repeat(32)));
+
+        assert!(verify_download(&response, true).is_ok());
+        assert!(response.download_path.exists());
+    }
+
+    #[test]
+    fn install_from_file_should_register_detected_version() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let config = config(&dir);
+
+        install_from_file(&archive, None, &config).unwrap();
+
+        let installation = config
+            .get_installation_dir()
+            .join("11.0.12-lts-local")
+            .join("installation");
+        assert!(installation.join("release").is_file());
+    }
+
+    #[test]
+    fn install_from_file_should_reject_other_version() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let archive = create_fixture_archive(dir.path());
+        let config = config(&dir);
+
+        let result = install_from_file(&archive, Some(&"17".parse().unwrap()), &config);
+
+        assert!(result.is_err());
+        assert!(!config
+            .get_installation_dir()
+            .join("11.0.12-lts-local")
+            .exists());
+    }
+
+    #[test]
+    fn failed_install_should_leave_no_version_or_staging_dir() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let jdk = dir.path().join("broken-jdk");
+        create_dir_all(&jdk).unwrap();
+        write(jdk.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        let config = config(&dir);
+
+        let error = install_from_dir(&jdk, None, &config).unwrap_err();
+
+        assert!(error.to_string().contains("does not contain"));
+        assert!(read_dir(config.get_installation_dir())
+            .unwrap()
+            .next()
+            .is_none());
+        let leftovers: Vec<_> = read_dir(config.get_base_dir_or_default())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".install"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn move_into_place_should_not_replace_existing_version() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let jdk = dir.path().join("staging").join("jdk");
+        create_dir_all(jdk.join("bin")).unwrap();
+        write(jdk.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        write(dir.path().join("staging").join(fixture_java("jdk")), "").unwrap();
+        let install_dir = dir.path().join("17.0.2-lts-adoptium");
+        create_dir_all(install_dir.join("installation")).unwrap();
+        write(install_dir.join("installation").join("marker"), "").unwrap();
+
+        let result = move_into_place(&jdk, &dir.path().join("staging"), &install_dir);
+
+        assert!(result.is_err());
+        assert!(install_dir.join("installation").join("marker").is_file());
+    }
+
+    #[test]
+    fn move_into_place_should_keep_only_java_home_of_macos_bundle() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let jdk = dir.path().join("staging").join("jdk");
+        let home = jdk.join("Contents").join("Home");
+        create_dir_all(home.join("bin")).unwrap();
+        write(home.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        write(home.join(fixture_java(".")), "").unwrap();
+        let install_dir = dir.path().join("17.0.2-lts-adoptium");
+
+        move_into_place(&jdk, &dir.path().join("staging"), &install_dir).unwrap();
+
+        assert!(install_dir.join("installation").join("release").is_file());
+        assert!(install_dir
+            .join("installation")
+            .join(fixture_java("."))
+            .is_file());
+    }
+
+    #[test]
+    fn install_from_dir_should_copy_jdk_and_detect_provider() {
+        let dir = TempDir::new("jvc-install").unwrap();
+        let jdk = dir.path().join("jdk-17.0.2+8");
+        create_dir_all(jdk.join("bin")).unwrap();
+        write(dir.path().join(fixture_java("jdk-17.0.2+8")), "").unwrap();
+        write(
+            jdk.join("release"),
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"17.0.2\"\nJAVA_RUNTIME_VERSION=\"17.0.2+8\"\n",
+        )
+        .unwrap();
+        let config = config(&dir);
+
+        install_from_dir(&jdk, Some(&"17".parse().unwrap()), &config).unwrap();
+
+        let installation = config
+            .get_installation_dir()
+            .join("17.0.2+8-lts-adoptium")
+            .join("installation");
+        assert!(installation.join(fixture_java(".")).is_file());
+        assert!(jdk.join("release").is_file());
+    }
 }
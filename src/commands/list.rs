@@ -1,22 +1,33 @@
 use crate::{
-    client::package_client::get_client,
+    client::package_client::get_configured_client,
     config::{JvcConfig, VersionRequirements},
-    version::Version,
+    version::{Version, VersionReq},
 };
 use std::{
+    env,
     fs::read_link,
     io::{Error, ErrorKind},
     path::{Path, PathBuf},
+    str::FromStr,
     vec,
 };
 
-use super::executor::Executor;
+use super::{
+    current::{current_version, CurrentVersion},
+    executor::Executor,
+    import::is_external,
+    use_version::current_shell_path,
+};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use colored::Colorize;
 use log::debug;
+use serde::Serialize;
 use structopt::StructOpt;
 
+/// Links followed from an alias before giving up, aliases only add one or two.
+const MAX_LINKS: usize = 8;
+
 #[derive(Debug, StructOpt)]
 pub struct List {
     /// Activate remote listing for feature versions
@@ -25,57 +36,167 @@ pub struct List {
 
     #[structopt(flatten)]
     pub requirements: VersionRequirements,
+
+    /// Output format: table, plain or json
+    #[structopt(long, default_value = "table", possible_values = &["table", "plain", "json"])]
+    pub format: ListFormat,
+
+    /// Print versions as json, same as --format json
+    #[structopt(long, conflicts_with = "format")]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// Decorated table for people
+    Table,
+    /// One tab separated line per version: full version, provider and aliases
+    Plain,
+    Json,
+}
+
+impl FromStr for ListFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "table" => Ok(ListFormat::Table),
+            "plain" => Ok(ListFormat::Plain),
+            "json" => Ok(ListFormat::Json),
+            _ => Err(anyhow!("Cannot parse list format {}", format)),
+        }
+    }
+}
+
+/// A listed version with its location, aliases and whether it is the default or in use.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListedVersion {
+    #[serde(flatten)]
+    pub version: Version,
+    /// Installation directory, missing for remote versions
+    pub path: Option<PathBuf>,
+    pub external: bool,
+    pub aliases: Vec<String>,
+    pub default: bool,
+    /// Used by the current shell
+    pub current: bool,
 }
 #[async_trait]
 impl Executor for List {
     async fn execute(self, config: JvcConfig) -> Result<()> {
         debug!(
             "running list command with provider {}",
-            &config.java_provider.as_str()
+            &config.provider().as_str()
         );
         let versions = if self.remote {
             debug!("try to retrieve list of available versions");
-            let provider = &config.java_provider;
-            let client = get_client(provider);
+            let config = JvcConfig {
+                java_provider: Some(config.provider().migrate()),
+                ..config.clone()
+            };
+            let client = get_configured_client(&config);
             client
-                .get_all_version(self.requirements)
+                .get_all_version(config.requirements(self.requirements))
                 .await
                 .context("Cannot get available versions")?
         } else {
             let installation_dir = config.get_installation_dir();
-            list_installed_versions(installation_dir)?
+            let mut versions: Vec<Version> = list_installed_versions(&installation_dir)?
                 .iter()
-                .map(|v| Version::new_from_disk(&*v))
-                .collect()
+                .filter_map(|v| Version::parse_disk_name(v).ok())
+                .collect();
+            versions.sort();
+            versions
         };
         debug!("Prepare list from versions: {:?}", versions);
-        let alias_versions = list_aliases_versions(config.aliases_dir())?;
+        let current = current_shell_path().ok().and_then(|shell_path| {
+            current_version(&shell_path, &env::current_dir().ok()?, &config).ok()
+        });
+        let listed = listed_versions(versions, self.remote, current.as_ref(), &config)?;
 
-        println!("###############################################################");
-        for v in versions {
-            let alias_versions = find_alias_version(&alias_versions, &v);
-            if v.is_lts() {
-                print!("{}", "*".blue());
-            } else {
-                print!(" ");
+        let format = if self.json {
+            ListFormat::Json
+        } else {
+            self.format
+        };
+        match format {
+            ListFormat::Json => println!("{}", serde_json::to_string_pretty(&listed)?),
+            ListFormat::Plain => {
+                for v in &listed {
+                    println!(
+                        "{}\t{}\t{}",
+                        v.version.value,
+                        v.version.source_name(),
+                        v.aliases.join(",")
+                    );
+                }
             }
-            print!("- {} ", v.value);
-            if !self.remote {
-                print!("- {}", v.provider.as_str().green());
+            ListFormat::Table => print_table(&listed, self.remote),
+        }
+
+        Ok(())
+    }
+}
+
+fn listed_versions(
+    versions: Vec<Version>,
+    remote: bool,
+    current: Option<&CurrentVersion>,
+    config: &JvcConfig,
+) -> Result<Vec<ListedVersion>> {
+    let alias_versions = list_aliases_versions(config.aliases_dir())?;
+    Ok(versions
+        .into_iter()
+        .map(|v| {
+            let aliases: Vec<String> = find_alias_version(&alias_versions, &v)
+                .into_iter()
+                .map(|alias| alias.alias_name)
+                .collect();
+            let path = if remote {
+                None
             } else {
-                print!(" ");
+                Some(config.get_installation_dir().join(v.as_disk_name()))
+            };
+            ListedVersion {
+                external: path.as_ref().map(is_external).unwrap_or(false),
+                default: aliases.iter().any(|alias| alias == "default"),
+                current: current.is_some_and(|current| {
+                    current.version == v.value.to_string() && current.provider == v.source_name()
+                }),
+                path,
+                aliases,
+                version: v,
             }
-            for alias_v in alias_versions {
-                print!(" [{}]", alias_v.alias_name)
+        })
+        .collect())
+}
+
+fn print_table(listed: &[ListedVersion], remote: bool) {
+    println!("###############################################################");
+    for listed_version in listed {
+        let v = &listed_version.version;
+        if v.is_lts() {
+            print!("{}", "*".blue());
+        } else {
+            print!(" ");
+        }
+        print!("- {} ", v.value);
+        if !remote {
+            print!("- {}", v.source_name().green());
+            if listed_version.external {
+                print!(" {}", "(external)".yellow());
             }
-            println!()
+        } else {
+            print!(" ");
         }
-
-        println!("###############################################################");
-        println!("{}", "All version marked with * are LTS version".blue());
-
-        Ok(())
+        for alias_name in &listed_version.aliases {
+            print!(" [{}]", alias_name)
+        }
+        println!()
     }
+
+    println!("###############################################################");
+    println!("{}", "All version marked with * are LTS version".blue());
 }
 
 // TODO: rewrite in a more functional style
@@ -100,7 +221,7 @@ pub fn list_installed_versions<P: AsRef<Path>>(installation_dir: P) -> Result<Ve
     Ok(vec)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AliasVersion {
     pub version: Option<Version>,
     pub is_invalid: bool,
@@ -121,36 +242,25 @@ impl AliasVersion {
             false
         }
     }
-    pub fn new(target_path: PathBuf, alias_path: PathBuf) -> Self {
-        let current_path = target_path.as_path();
+    /// Alias of the version installed in `target_path`, fails when that is not a version.
+    pub fn new(target_path: PathBuf, alias_path: PathBuf) -> Result<Self> {
         let real_file_name = target_path
             .file_name()
-            .expect("Cannot get name for version")
-            .to_str()
-            .expect("Cannot get name for version");
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let version = Version::parse_disk_name(&real_file_name)?;
 
-        Self {
-            alias_name: alias_path
-                .clone()
-                .file_name()
-                .expect("Cannot get alias file name")
-                .to_str()
-                .expect("Cannot get alias file name")
-                .to_string(),
+        Ok(Self {
+            alias_name: alias_name(&alias_path),
             alias_path,
             is_invalid: false,
-            target_path: Some(current_path.to_path_buf()),
-            version: Some(Version::new_from_disk(real_file_name)),
-        }
+            target_path: Some(target_path),
+            version: Some(version),
+        })
     }
     pub fn new_invalid_alias(alias_path: PathBuf) -> Self {
         Self {
-            alias_name: alias_path
-                .file_name()
-                .expect("Cannot get alias file name")
-                .to_str()
-                .expect("Cannot get alias file name")
-                .to_string(),
+            alias_name: alias_name(&alias_path),
             alias_path,
             is_invalid: true,
             target_path: None,
@@ -159,16 +269,44 @@ impl AliasVersion {
     }
 }
 
+fn alias_name(alias_path: &Path) -> String {
+    alias_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Last path of a chain of links, like an alias pointing to another alias.
+pub fn follow_links(path: &Path) -> Result<PathBuf> {
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_LINKS {
+        match read_link(&path) {
+            Ok(target) => {
+                path = match path.parent() {
+                    Some(parent) if target.is_relative() => parent.join(target),
+                    _ => target,
+                }
+            }
+            Err(_) if path.is_dir() => return Ok(path),
+            Err(_) => return Err(anyhow!("{:?} does not exist anymore", path)),
+        }
+    }
+    Err(anyhow!("Too many links to follow from {:?}", path))
+}
+
 pub fn list_aliases_versions<P: AsRef<Path>>(aliases_dir: P) -> Result<Vec<AliasVersion>> {
     let mut vec = vec![];
     for entry in aliases_dir.as_ref().read_dir()? {
         let element = entry?;
         let type_info = element.file_type()?;
         if type_info.is_symlink() {
-            let version = match read_link(element.path()) {
-                Ok(target_path) => AliasVersion::new(target_path, element.path()),
-                Err(_e) => AliasVersion::new_invalid_alias(element.path()),
-            };
+            // removed versions, or aliases of them, show up as invalid aliases
+            let version = follow_links(&element.path())
+                .and_then(|target_path| AliasVersion::new(target_path, element.path()))
+                .unwrap_or_else(|e| {
+                    debug!("Invalid alias {:?}: {}", element.path(), e);
+                    AliasVersion::new_invalid_alias(element.path())
+                });
             vec.push(version);
         };
     }
@@ -191,6 +329,7 @@ fn find_alias_version(alias_versions: &[AliasVersion], v: &Version) -> Vec<Alias
     result
 }
 #[allow(dead_code)]
+#[derive(Serialize)]
 pub struct VersionPath {
     path: PathBuf,
     version: Version,
@@ -202,23 +341,221 @@ impl VersionPath {
     }
 }
 
-pub fn find_version(to_version: u8, config: &JvcConfig) -> Result<Option<VersionPath>> {
+pub fn find_version(to_version: &VersionReq, config: &JvcConfig) -> Result<Option<VersionPath>> {
     let all_versions = list_installed_versions(config.get_installation_dir())?;
     debug!("Identified the following versions: {:?}", all_versions);
 
-    let selected_version = all_versions.iter().find(|v| {
-        to_version
-            == Version::new_from_disk(&**v)
-                .to_version_number()
-                .expect("Cannot parse installed version")
-    });
+    let selected_version = all_versions
+        .iter()
+        .filter_map(|name| {
+            Version::parse_disk_name(name)
+                .map_err(|e| debug!("Ignore installed version {}: {}", name, e))
+                .ok()
+                .map(|version| (name, version))
+        })
+        .filter(|(_, version)| to_version.matches(&version.value))
+        .max_by(|(_, v1), (_, v2)| v1.value.cmp(&v2.value));
     let installation_dir = config.get_installation_dir();
-    if let Some(ver) = selected_version {
+    if let Some((name, version)) = selected_version {
         Ok(Some(VersionPath {
-            path: installation_dir.join(ver.to_string()),
-            version: Version::new_from_disk(&*ver),
+            path: installation_dir.join(name),
+            version,
         }))
     } else {
         Ok(None)
     }
 }
+
+/// Directory of the alias `name` or of the highest installed version matching it.
+pub fn find_version_or_alias(name: &str, config: &JvcConfig) -> Result<Option<PathBuf>> {
+    let alias_dir = config.aliases_dir().join(name);
+    if read_link(&alias_dir).is_ok() {
+        return Ok(Some(alias_dir));
+    }
+    match name.parse::<VersionReq>() {
+        Ok(version) => Ok(find_version(&version, config)?.map(|v| v.path().to_owned())),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_version, find_version_or_alias, list_aliases_versions, listed_versions, List,
+        ListFormat,
+    };
+    use crate::{
+        commands::current::{CurrentVersion, Source},
+        config::JvcConfig,
+        provider::Provider,
+        version::Version,
+    };
+    use serde_json::json;
+    use std::{fs::create_dir_all, path::PathBuf};
+    use structopt::StructOpt;
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    fn config_with_versions(dir: &TempDir, versions: &[&str]) -> JvcConfig {
+        let config = JvcConfig {
+            base_dir: Some(dir.path().to_path_buf()),
+            ..JvcConfig::default()
+        };
+        for version in versions {
+            create_dir_all(config.get_installation_dir().join(version)).unwrap();
+        }
+        config
+    }
+
+    #[test]
+    fn find_version_should_select_highest_matching_version() {
+        let dir = TempDir::new("jvc-list").unwrap();
+        let config = config_with_versions(
+            &dir,
+            &[
+                "8-lts-adoptopenjdk",
+                "11.0.12+7-lts-azul",
+                "11.0.20+8-lts-azul",
+                "17.0.2+8-lts-adoptopenjdk",
+            ],
+        );
+        let find = |req: &str| {
+            find_version(&req.parse().unwrap(), &config)
+                .unwrap()
+                .map(|version_path| version_path.version.to_string())
+        };
+
+        assert_eq!(find("11"), Some("11.0.20+8".to_owned()));
+        assert_eq!(find("11.0.12"), Some("11.0.12+7".to_owned()));
+        assert_eq!(find(">=11"), Some("17.0.2+8".to_owned()));
+        assert_eq!(find("<11"), Some("8".to_owned()));
+        assert_eq!(find("21"), None);
+    }
+
+    #[test]
+    fn find_version_or_alias_should_prefer_aliases() {
+        let dir = TempDir::new("jvc-list").unwrap();
+        let config = config_with_versions(&dir, &["11.0.20+8-lts-azul", "17.0.2+8-lts-azul"]);
+        let installation_dir = config.get_installation_dir();
+        create_dir_all(config.aliases_dir()).unwrap();
+        symlink_dir(
+            installation_dir.join("11.0.20+8-lts-azul"),
+            config.aliases_dir().join("17"),
+        )
+        .unwrap();
+        symlink_dir(
+            installation_dir.join("17.0.2+8-lts-azul"),
+            config.aliases_dir().join("default"),
+        )
+        .unwrap();
+        let find = |name: &str| find_version_or_alias(name, &config).unwrap();
+
+        assert_eq!(find("default"), Some(config.aliases_dir().join("default")));
+        assert_eq!(find("17"), Some(config.aliases_dir().join("17")));
+        assert_eq!(
+            find(">=11"),
+            Some(installation_dir.join("17.0.2+8-lts-azul"))
+        );
+        assert_eq!(find("missing"), None);
+        assert_eq!(find("21"), None);
+    }
+
+    #[test]
+    fn find_version_should_ignore_unknown_directories() {
+        let dir = TempDir::new("jvc-list").unwrap();
+        let config = config_with_versions(&dir, &["not-a-version", "17-lts-azul"]);
+
+        let version_path = find_version(&"17".parse().unwrap(), &config)
+            .unwrap()
+            .unwrap();
+        assert_eq!(version_path.version.provider, Provider::Azul);
+    }
+
+    #[test]
+    fn listed_versions_should_serialize_stable_schema() {
+        let dir = TempDir::new("jvc-list").unwrap();
+        let config = config_with_versions(&dir, &["11.0.20+8-lts-azul", "17.0.2+8-lts-adoptium"]);
+        symlink_dir(
+            config.get_installation_dir().join("17.0.2+8-lts-adoptium"),
+            config.default_version_dir(),
+        )
+        .unwrap();
+        let current = CurrentVersion {
+            provider: "azul".to_owned(),
+            version: "11.0.20+8".to_owned(),
+            lts: true,
+            path: PathBuf::from("/jvc/11/installation"),
+            source: Source::Shell,
+        };
+        let versions = vec![
+            Version::parse_disk_name("11.0.20+8-lts-azul").unwrap(),
+            Version::parse_disk_name("17.0.2+8-lts-adoptium").unwrap(),
+        ];
+
+        let listed = listed_versions(versions, false, Some(&current), &config).unwrap();
+
+        let json = serde_json::to_value(&listed).unwrap();
+        assert_eq!(
+            json[1],
+            json!({
+                "version": 17,
+                "full_version": "17.0.2+8",
+                "lts": true,
+                "provider": "adoptium",
+                "distribution": null,
+                "path": config.get_installation_dir().join("17.0.2+8-lts-adoptium"),
+                "external": false,
+                "aliases": ["default"],
+                "default": true,
+                "current": false
+            })
+        );
+        assert_eq!(json[0]["current"], json!(true));
+        assert_eq!(json[0]["default"], json!(false));
+    }
+
+    #[test]
+    fn aliases_of_aliases_should_resolve_to_the_version() {
+        let dir = TempDir::new("jvc-list").unwrap();
+        let config = config_with_versions(&dir, &["17.0.2-lts-adoptium"]);
+        let aliases_dir = config.aliases_dir();
+        symlink_dir(
+            config.get_installation_dir().join("17.0.2-lts-adoptium"),
+            aliases_dir.join("lts"),
+        )
+        .unwrap();
+        symlink_dir(aliases_dir.join("lts"), config.default_version_dir()).unwrap();
+        create_dir_all(dir.path().join("not-a-version")).unwrap();
+        symlink_dir(dir.path().join("not-a-version"), aliases_dir.join("other")).unwrap();
+
+        let mut aliases = list_aliases_versions(&aliases_dir).unwrap();
+        aliases.sort_by(|a, b| a.alias_name.cmp(&b.alias_name));
+
+        let versions: Vec<(String, Option<String>)> = aliases
+            .iter()
+            .map(|alias| {
+                (
+                    alias.alias_name.clone(),
+                    alias.version.as_ref().map(|v| v.value.to_string()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            versions,
+            vec![
+                ("default".to_owned(), Some("17.0.2".to_owned())),
+                ("lts".to_owned(), Some("17.0.2".to_owned())),
+                ("other".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_flag_should_conflict_with_format() {
+        let list = List::from_iter_safe(&["list", "--json"]).unwrap();
+        assert!(list.json);
+        assert_eq!(list.format, ListFormat::Table);
+
+        assert!(List::from_iter_safe(&["list", "--json", "--format", "plain"]).is_err());
+    }
+}
@@ -1,9 +1,18 @@
 pub mod alias;
+pub mod cache;
+pub mod completions;
+pub mod config;
+pub mod current;
 pub mod default;
+pub mod doctor;
 pub mod env;
+pub mod exec;
 pub mod executor;
+pub mod import;
 pub mod install;
 pub mod list;
 pub mod package;
 pub mod remove;
+pub mod use_version;
+pub mod which;
 pub mod windows;
@@ -1,11 +1,16 @@
-use std::{fs::remove_dir_all, path::Path};
+use std::{
+    fs::{remove_dir, remove_dir_all},
+    path::Path,
+};
 
-use super::{executor::Executor, list::find_version};
+use super::{executor::Executor, import::is_external, list::find_version};
+use crate::version::VersionReq;
 use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, info};
 use structopt::StructOpt;
+use symlink::remove_symlink_dir;
 #[derive(Debug, StructOpt)]
 pub struct Remove {
     pub version: String,
@@ -14,9 +19,10 @@ pub struct Remove {
 #[async_trait]
 impl Executor for Remove {
     async fn execute(self, config: crate::config::JvcConfig) -> Result<()> {
-        let version = self.version.parse::<u8>()?;
+        let _lock = config.lock()?;
+        let version = self.version.parse::<VersionReq>()?;
         let applicable_version =
-            find_version(version, &config)?.ok_or(anyhow!("Cannot find requested version!"))?;
+            find_version(&version, &config)?.ok_or(anyhow!("Cannot find requested version!"))?;
 
         info!("Removing version: {}", version);
         debug!("Path to version: {:?}", applicable_version.path());
@@ -25,16 +31,25 @@ impl Executor for Remove {
     }
 }
 
+/// External versions are only unregistered, the JDK they link to is kept.
 fn remove_version<T: AsRef<Path>>(path: T) -> Result<()> {
+    if is_external(path.as_ref()) {
+        debug!("Unregister external version {:?}", path.as_ref());
+        remove_symlink_dir(path.as_ref().join("installation"))?;
+        return remove_dir(path.as_ref()).map_err(|_e| anyhow!("Cannot get path"));
+    }
     remove_dir_all(path.as_ref()).map_err(|_e| anyhow!("Cannot get path"))
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::Remove;
+    use super::{remove_version, Remove};
     use crate::{commands::executor::Executor, config::JvcConfig};
     use anyhow::Result;
+    use std::fs::{create_dir_all, write};
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
 
     #[tokio::test]
     async fn test_remove_execution_for_big_version() -> Result<()> {
@@ -45,4 +60,20 @@ mod tests {
         assert!(result.is_err());
         Ok(())
     }
+
+    #[test]
+    fn remove_external_version_should_keep_linked_jdk() {
+        let dir = TempDir::new("jvc-remove").unwrap();
+        let jdk = dir.path().join("jdk-17");
+        create_dir_all(&jdk).unwrap();
+        write(jdk.join("release"), "JAVA_VERSION=\"17.0.2\"\n").unwrap();
+        let version_dir = dir.path().join("17.0.2-lts-local");
+        create_dir_all(&version_dir).unwrap();
+        symlink_dir(&jdk, version_dir.join("installation")).unwrap();
+
+        remove_version(&version_dir).unwrap();
+
+        assert!(!version_dir.exists());
+        assert!(jdk.join("release").is_file());
+    }
 }
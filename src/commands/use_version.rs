@@ -0,0 +1,245 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs::{read_link, rename},
+    io::{stdin, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+};
+
+use super::{
+    executor::Executor,
+    install::Install,
+    list::{find_version, find_version_or_alias},
+};
+use crate::{
+    config::{JvcConfig, VersionRequirements},
+    project::{find_project_version, project_version_req, ProjectVersion},
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use log::{debug, info};
+use structopt::StructOpt;
+use symlink::{remove_symlink_dir, symlink_dir};
+
+#[derive(Debug, StructOpt)]
+pub struct Use {
+    /// Installed version or alias to use in this shell, like 17, >=11 or lts.
+    /// Read from .jvcrc, .java-version or .sdkmanrc when missing
+    pub version: Option<String>,
+
+    /// Do not complain when no .jvcrc, .java-version or .sdkmanrc file is found
+    #[structopt(long)]
+    pub quiet: bool,
+
+    /// Install the pinned version without asking when it is missing
+    #[structopt(long)]
+    pub install_if_missing: bool,
+}
+
+#[async_trait]
+impl Executor for Use {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let shell_path = current_shell_path()?;
+        check_shell_link(&shell_path)?;
+        if let Some(version) = &self.version {
+            let target = find_version_or_alias(version, &config)?.ok_or_else(|| {
+                anyhow!(
+                    "Version or alias {} is not installed. Run `jvc install {}` first.",
+                    version,
+                    version
+                )
+            })?;
+            switch_shell_version(&shell_path, &target)?;
+            info!("Using java {} in this shell", version.green());
+            return Ok(());
+        }
+
+        let project = match find_project_version(env::current_dir()?)? {
+            Some(project) => project,
+            None if self.quiet => return Ok(()),
+            None => {
+                return Err(anyhow!(
+                    "Cannot find a .jvcrc, .java-version or .sdkmanrc file in current directory or its parents"
+                ))
+            }
+        };
+        debug!(
+            "Project file {:?} requires version {}",
+            project.file, project.version
+        );
+
+        let target = match resolve_project_version(&project, &config)? {
+            Some(target) => target,
+            None => {
+                self.install_missing_version(&project, config.clone())
+                    .await?;
+                resolve_project_version(&project, &config)?
+                    .ok_or_else(|| anyhow!("Cannot find requested version!"))?
+            }
+        };
+
+        if read_link(&shell_path).ok().as_deref() == Some(target.as_path()) {
+            debug!("Shell already uses {:?}", target);
+            return Ok(());
+        }
+        switch_shell_version(&shell_path, &target)?;
+        info!(
+            "Using java {} from {}",
+            project.version.green(),
+            project.file.to_string_lossy()
+        );
+        Ok(())
+    }
+}
+
+impl Use {
+    async fn install_missing_version(
+        &self,
+        project: &ProjectVersion,
+        config: JvcConfig,
+    ) -> Result<()> {
+        let version = project_version_req(&project.version).ok_or_else(|| {
+            anyhow!(
+                "Version {} from {:?} is neither installed nor an alias",
+                project.version,
+                project.file
+            )
+        })?;
+
+        if !self.install_if_missing && !confirm_install(&project.version)? {
+            return Err(anyhow!(
+                "Version {} is not installed. Run `jvc install {}` first.",
+                project.version,
+                version
+            ));
+        }
+
+        Install {
+            version: Some(version),
+            requirements: config
+                .settings
+                .requirements()
+                .or(VersionRequirements::default()),
+            skip_checksum: false,
+            from_file: None,
+            from_dir: None,
+        }
+        .execute(config)
+        .await
+    }
+}
+
+/// Resolve the project version to an alias or an installed version directory.
+pub fn resolve_project_version(
+    project: &ProjectVersion,
+    config: &JvcConfig,
+) -> Result<Option<PathBuf>> {
+    let alias_dir = config.aliases_dir().join(&project.version);
+    if read_link(&alias_dir).is_ok() {
+        return Ok(Some(alias_dir));
+    }
+
+    match project_version_req(&project.version) {
+        Some(version) => {
+            Ok(find_version(&version, config)?.map(|version_path| version_path.path().to_owned()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn confirm_install(version: &str) -> Result<bool> {
+    if !stdin().is_terminal() {
+        return Ok(false);
+    }
+    eprint!(
+        "Java version {} is not installed. Do you want to install it? [y/N] ",
+        version
+    );
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Link created for the current shell by `jvc env`.
+pub fn current_shell_path() -> Result<PathBuf> {
+    env::var_os("JVC_SHELL_PATH")
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("JVC_SHELL_PATH is not set. Please evaluate `jvc env` first."))
+}
+
+/// Without the link of `jvc env` the shell would not see a new version anyway.
+fn check_shell_link(shell_path: &Path) -> Result<()> {
+    if read_link(shell_path).is_err() {
+        return Err(anyhow!(
+            "{:?} is not a jvc shell link. Please evaluate `jvc env` first.",
+            shell_path
+        ));
+    }
+    Ok(())
+}
+
+/// Point the shell link to `target`. The new link is created next to it and renamed over it,
+/// so the shell never sees a missing link.
+pub fn switch_shell_version(shell_path: &Path, target: &Path) -> Result<()> {
+    check_shell_link(shell_path)?;
+    let mut new_link = OsString::from(shell_path.as_os_str());
+    new_link.push(format!(".{}", process::id()));
+    let new_link = PathBuf::from(new_link);
+    if read_link(&new_link).is_ok() {
+        remove_symlink_dir(&new_link)?;
+    }
+    symlink_dir(target, &new_link)?;
+    replace_link(&new_link, shell_path)
+        .map_err(|e| anyhow!("Cannot switch {:?} to {:?}: {}", shell_path, target, e))
+}
+
+#[cfg(unix)]
+fn replace_link(new_link: &Path, shell_path: &Path) -> std::io::Result<()> {
+    rename(new_link, shell_path)
+}
+
+/// Directory links cannot be renamed over each other on windows, the old one goes first.
+#[cfg(windows)]
+fn replace_link(new_link: &Path, shell_path: &Path) -> std::io::Result<()> {
+    if read_link(shell_path).is_ok() {
+        remove_symlink_dir(shell_path)?;
+    }
+    rename(new_link, shell_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::switch_shell_version;
+    use std::fs::{create_dir_all, read_dir, read_link};
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    #[test]
+    fn switch_should_replace_shell_link() {
+        let dir = TempDir::new("jvc-use").unwrap();
+        let first = dir.path().join("17.0.2-lts-adoptium");
+        let second = dir.path().join("11.0.14-lts-azul");
+        create_dir_all(&first).unwrap();
+        create_dir_all(&second).unwrap();
+        let shell_path = dir.path().join("jvc_shell_1_2");
+        symlink_dir(&first, &shell_path).unwrap();
+
+        switch_shell_version(&shell_path, &second).unwrap();
+
+        assert_eq!(read_link(&shell_path).unwrap(), second);
+        assert_eq!(read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn switch_should_fail_without_shell_link() {
+        let dir = TempDir::new("jvc-use").unwrap();
+        let version = dir.path().join("17.0.2-lts-adoptium");
+        create_dir_all(&version).unwrap();
+        let shell_path = dir.path().join("jvc_shell_1_2");
+
+        assert!(switch_shell_version(&shell_path, &version).is_err());
+        assert_eq!(read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
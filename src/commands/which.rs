@@ -0,0 +1,60 @@
+use std::{env, path::PathBuf};
+
+use super::{current::current_version, executor::Executor, use_version::current_shell_path};
+use crate::config::JvcConfig;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct Which {
+    /// JDK tool to locate, like java, javac or jar
+    pub tool: String,
+
+    /// Print the tool path as json
+    #[structopt(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolPath {
+    tool: String,
+    version: String,
+    path: PathBuf,
+}
+
+#[async_trait]
+impl Executor for Which {
+    async fn execute(self, config: JvcConfig) -> Result<()> {
+        let current = current_version(&current_shell_path()?, &env::current_dir()?, &config)?;
+        let path = current.path.join("bin").join(tool_file_name(&self.tool));
+        if !path.is_file() {
+            return Err(anyhow!(
+                "Cannot find {} in java {}",
+                self.tool,
+                current.version
+            ));
+        }
+
+        if self.json {
+            let tool = ToolPath {
+                tool: self.tool,
+                version: current.version,
+                path,
+            };
+            println!("{}", serde_json::to_string_pretty(&tool)?);
+        } else {
+            println!("{}", path.to_string_lossy());
+        }
+        Ok(())
+    }
+}
+
+fn tool_file_name(tool: &str) -> String {
+    if cfg!(windows) && !tool.ends_with(".exe") {
+        format!("{}.exe", tool)
+    } else {
+        tool.to_owned()
+    }
+}
@@ -4,7 +4,7 @@ use std::process::Command;
 use crate::config::JvcConfig;
 
 use super::executor::Executor;
-use crate::commands::env::make_symlink;
+use crate::commands::env::{make_symlink, shell_java_home};
 use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -19,18 +19,21 @@ use winreg::{
 pub struct Setup {}
 
 impl Setup {
-    pub async fn set_persistent_env_var(&self, name: &str, value: &str) {
-        let permanent_set = format!("SETX {} {}", name, value);
-
-        debug!("running command: {} on cmd", permanent_set);
-        Command::new("cmd")
-            .args(&["/C", permanent_set.as_ref()])
-            .output()
-            .or_else(|e| {
-                debug!("run into error on command execution: {:#?}", e);
-                Err(e)
-            })
-            .ok();
+    /// Persist a user variable with setx. Arguments are passed without a shell, so values with
+    /// spaces, like a profile path, are kept as one argument.
+    pub async fn set_persistent_env_var(&self, name: &str, value: &str) -> Result<()> {
+        debug!("running command: setx {} {:?}", name, value);
+        let output = Command::new("setx").args([name, value]).output()?;
+        if !output.status.success() {
+            // setx reports errors on stdout or stderr depending on the version
+            let message = format!(
+                "{} {}",
+                String::from_utf8_lossy(&output.stdout).trim(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Err(anyhow!("Cannot persist {}: {}", name, message.trim()));
+        }
+        Ok(())
     }
 
     pub async fn set_persistent_path(&self, value: &str) -> Result<()> {
@@ -68,8 +71,9 @@ impl Setup {
 #[async_trait]
 impl Executor for Setup {
     async fn execute(self, config: JvcConfig) -> Result<()> {
+        // the link is persisted for every new shell, so it is not tracked for cleanup
         let shell_path = make_symlink(&config);
-        let _bin_path = shell_path.join("installation").join("bin");
+        let java_home = shell_java_home(&shell_path);
 
         let shell_path_as_str = shell_path
             .to_str()
@@ -83,12 +87,22 @@ impl Executor for Setup {
             .ok_or(anyhow!("Cannot read base directory."))?;
 
         self.set_persistent_env_var("JVC_SHELL_PATH", shell_path_as_str)
-            .await;
-        self.set_persistent_env_var("JVC_DIR", jvc_dir_as_str).await;
-        self.set_persistent_env_var("JVC_LOGLEVEL", config.log_level.into())
-            .await;
-        self.set_persistent_env_var("JVC_PROVIDER", &config.java_provider.as_str())
-            .await;
+            .await?;
+        self.set_persistent_env_var("JVC_DIR", jvc_dir_as_str)
+            .await?;
+        self.set_persistent_env_var(
+            "JAVA_HOME",
+            java_home.to_str().ok_or(anyhow!("Cannot set JAVA_HOME."))?,
+        )
+        .await?;
+        if let Some(log_level) = config.log_level {
+            self.set_persistent_env_var("JVC_LOGLEVEL", log_level.into())
+                .await?;
+        }
+        if let Some(provider) = &config.java_provider {
+            self.set_persistent_env_var("JVC_PROVIDER", &provider.as_str())
+                .await?;
+        }
 
         let path_value = format!("%JVC_SHELL_PATH%");
         let _result = self.set_persistent_path(path_value.as_ref()).await?;
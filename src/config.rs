@@ -1,5 +1,13 @@
-use crate::{loglevel::LogLevel, provider::Provider, version::Version};
-use anyhow::Result;
+use crate::{
+    cache::{PackageCache, DEFAULT_MAX_SIZE},
+    lock::StateLock,
+    loglevel::LogLevel,
+    provider::Provider,
+    settings::{Settings, CONFIG_FILE},
+    shell_links::SHELLS_DIR,
+    version::{Version, VersionReq},
+};
+use anyhow::{Error, Result};
 use dirs::home_dir;
 use log::debug;
 use std::{
@@ -8,31 +16,30 @@ use std::{
 };
 use structopt::StructOpt;
 
-#[derive(Debug, StructOpt)]
+#[derive(Debug, Clone, Default, StructOpt)]
 pub struct JvcConfig {
     /// The root directory for jvc. This will contain aliases, all downloaded versions and optional config.
     #[structopt(long = "jvc-dir", env = "JVC_DIR", global = true)]
     pub base_dir: Option<PathBuf>,
 
-    /// Provider used to obtain java version. Possible options: adoptopenjdk or azul.
-    #[structopt(
-        long = "provider",
-        short = "p",
-        default_value = "adoptopenjdk",
-        env = "JVC_PROVIDER",
-        global = true
-    )]
-    pub java_provider: Provider,
+    /// Provider used to obtain java version. Possible options: adoptium, azul, disco or github.
+    /// adoptopenjdk is still accepted and uses adoptium.
+    /// Default value: provider from config.toml or adoptium
+    #[structopt(long = "provider", short = "p", env = "JVC_PROVIDER", global = true)]
+    pub java_provider: Option<Provider>,
 
     /// The log level for jvc. Possible options: debug, info, error, silent
-    #[structopt(
-        long = "log-level",
-        short = "l",
-        env = "JVC_LOGLEVEL",
-        default_value = "info",
-        global = true
-    )]
-    pub log_level: LogLevel,
+    /// Default value: log_level from config.toml or info
+    #[structopt(long = "log-level", short = "l", env = "JVC_LOGLEVEL", global = true)]
+    pub log_level: Option<LogLevel>,
+
+    /// Wait for other jvc processes changing installed versions instead of failing
+    #[structopt(long, global = true)]
+    pub wait: bool,
+
+    /// Defaults read from config.toml in the root directory
+    #[structopt(skip)]
+    pub settings: Settings,
 }
 
 #[derive(Debug, Clone, StructOpt)]
@@ -40,7 +47,7 @@ pub struct VersionRequirements {
     /// Java architecture
     ///
     /// Possible values Azul: x86, arm, mips, ppc, sparcv9
-    /// Possible values Adoptopenjdk: x64, x32, ppc64, ppc64le, s390x, aarch64, arm, sparcv9, riscv64
+    /// Possible values Adoptium, Adoptopenjdk: x64, x32, ppc64, ppc64le, s390x, aarch64, arm, sparcv9, riscv64
     /// Default value: try detect based on machine info or x86/x64
     #[structopt(long)]
     pub arch: Option<String>,
@@ -48,7 +55,7 @@ pub struct VersionRequirements {
     /// Image type
     ///
     /// Possible values Azul: jdk, jre
-    /// Possible values Adoptopenjdk: jdk, jre, testimage, debugimage, staticlibs
+    /// Possible values Adoptium, Adoptopenjdk: jdk, jre, testimage, debugimage, staticlibs
     /// Default value: jdk
     #[structopt(long, short = "it")]
     pub image_type: Option<String>,
@@ -56,7 +63,7 @@ pub struct VersionRequirements {
     /// JVM implementation
     ///
     /// Possible values Azul:
-    /// Possible values Adoptopenjdk: hotspot, openj9
+    /// Possible values Adoptium, Adoptopenjdk: hotspot, openj9
     /// Default value: hotspot
     #[structopt(long, short = "ji")]
     pub jvm_impl: Option<String>,
@@ -64,7 +71,7 @@ pub struct VersionRequirements {
     /// Heap size
     ///
     /// Possible values Azul:
-    /// Possible values Adoptopenjdk: normal, large
+    /// Possible values Adoptium, Adoptopenjdk: normal, large
     /// Default value: normal
     #[structopt(long)]
     pub heap_size: Option<String>,
@@ -72,21 +79,22 @@ pub struct VersionRequirements {
     /// Release type
     ///
     /// Possible values Azul:
-    /// Possible values Adoptopenjdk: ga, ea
+    /// Possible values Adoptium, Adoptopenjdk: ga, ea
     /// Default value: ga
     #[structopt(long)]
     pub release_type: Option<String>,
 
     /// Possible values Azul: N/A
+    /// Possible values Adoptium: eclipse
     /// Possible values Adoptopenjdk: adoptopenjdk, openjdk
-    /// Default value: adoptopenjdk for Adoptopenjdk
+    /// Default value: eclipse for Adoptium, adoptopenjdk for Adoptopenjdk
     #[structopt(long, short = "ve")]
     pub vendor: Option<String>,
 
     /// Project implementation
     ///
     /// Possible values Azul: N/A
-    /// Possible values Adoptopenjdk: jdk, valhalla, metropolis, jfr, shenandoah
+    /// Possible values Adoptium, Adoptopenjdk: jdk, valhalla, metropolis, jfr, shenandoah
     /// Default value: N/A
     #[structopt(long)]
     pub project: Option<String>,
@@ -94,10 +102,33 @@ pub struct VersionRequirements {
     /// Operating system values
     ///
     /// Possible values Azul: linux, linux_musl, macos, windows, solaris, qnx
-    /// Possible values Adoptopenjdk:  linux, windows, mac, solaris, aix, alpine-linux
+    /// Possible values Adoptium, Adoptopenjdk:  linux, windows, mac, solaris, aix, alpine-linux
     /// Default value: try detect from machine or fail
     #[structopt(long)]
     pub os: Option<String>,
+
+    /// GitHub repository used by the github provider, as owner/name
+    ///
+    /// Examples: SAP/SapMachine, adoptium/temurin17-binaries
+    /// Default value: SAP/SapMachine
+    #[structopt(long)]
+    pub github_repo: Option<String>,
+
+    /// Release asset name pattern used by the github provider
+    ///
+    /// `*` matches any text, {os}, {arch}, {image_type} and {ext} are replaced with requested values.
+    /// Example for adoptium/temurin17-binaries: OpenJDK*-{image_type}_{arch}_{os}_hotspot_*.{ext}
+    /// Default value: *{image_type}*{os}*{arch}*.{ext}
+    #[structopt(long)]
+    pub asset_pattern: Option<String>,
+
+    /// Distribution installed by the disco provider
+    ///
+    /// Possible values: temurin, zulu, corretto, liberica, sap_machine, microsoft, graalvm_ce17, semeru and
+    /// any other distribution name known by the foojay Disco API
+    /// Default value: temurin
+    #[structopt(long)]
+    pub distribution: Option<String>,
 }
 
 impl Default for VersionRequirements {
@@ -124,9 +155,12 @@ impl Default for VersionRequirements {
             jvm_impl: Some("hotspot".to_owned()),
             heap_size: Some("normal".to_owned()),
             release_type: Some("ga".to_owned()),
-            vendor: Some("adoptopenjdk".to_owned()),
+            vendor: None,
             project: Some("jdk".to_owned()),
             os: Some(os.to_owned()),
+            github_repo: None,
+            asset_pattern: None,
+            distribution: None,
         }
     }
 }
@@ -136,20 +170,82 @@ pub struct ClientConfig {
     pub download_dir: PathBuf,
     pub base_url: String,
     pub version: Version,
+    /// Exact version or range the client should resolve within `version`
+    pub version_req: VersionReq,
+    /// Packages downloaded before, reused when the provider announces the same checksum
+    pub cache: Option<PackageCache>,
 }
 
-// not sure if needed
-impl Default for JvcConfig {
-    fn default() -> Self {
+impl VersionRequirements {
+    /// Fill the requirements not given on the command line from `defaults`.
+    pub fn or(self, defaults: VersionRequirements) -> Self {
         Self {
-            base_dir: None,
-            java_provider: Provider::AdoptOpenJDK,
-            log_level: LogLevel::Info,
+            arch: self.arch.or(defaults.arch),
+            image_type: self.image_type.or(defaults.image_type),
+            jvm_impl: self.jvm_impl.or(defaults.jvm_impl),
+            heap_size: self.heap_size.or(defaults.heap_size),
+            release_type: self.release_type.or(defaults.release_type),
+            vendor: self.vendor.or(defaults.vendor),
+            project: self.project.or(defaults.project),
+            os: self.os.or(defaults.os),
+            github_repo: self.github_repo.or(defaults.github_repo),
+            asset_pattern: self.asset_pattern.or(defaults.asset_pattern),
+            distribution: self.distribution.or(defaults.distribution),
         }
     }
 }
 
 impl JvcConfig {
+    /// Load config.toml from the root directory into `settings`.
+    pub fn with_settings(self) -> Result<Self> {
+        let settings = Settings::load(self.settings_file())?;
+        Ok(Self { settings, ..self })
+    }
+
+    /// Like [`JvcConfig::with_settings`], but an invalid config.toml is returned as error next to
+    /// the settings that could be read, or the defaults when it cannot be parsed.
+    pub fn with_settings_lenient(self) -> (Self, Option<Error>) {
+        let path = self.settings_file();
+        let (settings, error) = match Settings::load(&path) {
+            Ok(settings) => (settings, None),
+            Err(e) => (Settings::load_unchecked(&path).unwrap_or_default(), Some(e)),
+        };
+        (Self { settings, ..self }, error)
+    }
+
+    /// Lock held while installed versions, aliases, downloads or the cache change.
+    pub fn lock(&self) -> Result<StateLock> {
+        Ok(StateLock::acquire(
+            self.get_base_dir_or_default(),
+            self.wait,
+        )?)
+    }
+
+    pub fn settings_file(&self) -> PathBuf {
+        self.get_base_dir_or_default().join(CONFIG_FILE)
+    }
+
+    /// Provider from the command line or environment, then config.toml, then adoptium.
+    pub fn provider(&self) -> Provider {
+        self.java_provider
+            .clone()
+            .or_else(|| self.settings.provider())
+            .unwrap_or(Provider::Adoptium)
+    }
+
+    /// Log level from the command line or environment, then config.toml, then info.
+    pub fn log_level(&self) -> LogLevel {
+        self.log_level
+            .clone()
+            .or_else(|| self.settings.log_level())
+            .unwrap_or(LogLevel::Info)
+    }
+
+    /// Requirements given on the command line completed with the defaults from config.toml.
+    pub fn requirements(&self, requirements: VersionRequirements) -> VersionRequirements {
+        requirements.or(self.settings.requirements())
+    }
+
     pub fn get_base_dir_or_default(&self) -> PathBuf {
         let path = self.base_dir.clone().unwrap_or(
             home_dir()
@@ -178,6 +274,23 @@ impl JvcConfig {
         )
     }
 
+    pub fn get_cache_dir(&self) -> PathBuf {
+        create_all_dir_if_missing(self.get_base_dir_or_default().join("cache"))
+    }
+
+    /// Package cache limited to `cache.max_size` from config.toml.
+    pub fn package_cache(&self) -> PackageCache {
+        PackageCache::new(
+            self.get_cache_dir(),
+            self.settings.cache_max_size().unwrap_or(DEFAULT_MAX_SIZE),
+        )
+    }
+
+    /// Records of the shell links created by `jvc env`.
+    pub fn shells_dir(&self) -> PathBuf {
+        create_all_dir_if_missing(self.get_base_dir_or_default().join(SHELLS_DIR))
+    }
+
     pub fn default_version_dir(&self) -> PathBuf {
         self.aliases_dir().join("default")
     }
@@ -205,26 +318,69 @@ pub fn new_client_config(
     version: Version,
     requirements: VersionRequirements,
 ) -> ClientConfig {
-    let provider = &config.java_provider;
-    match provider {
+    let provider = config.provider();
+    let client_config = match provider {
+        Provider::Adoptium => ClientConfig {
+            base_url: "https://api.adoptium.net/v3".to_owned(),
+            requirements,
+            download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
+            version,
+            cache: Some(config.package_cache()),
+        },
         Provider::AdoptOpenJDK => ClientConfig {
             base_url: "https://api.adoptopenjdk.net/v3".to_owned(),
             requirements,
             download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
             version,
+            cache: Some(config.package_cache()),
+        },
+        Provider::Disco => ClientConfig {
+            base_url: "https://api.foojay.io/disco/v3.0".to_owned(),
+            requirements,
+            download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
+            version,
+            cache: Some(config.package_cache()),
+        },
+        Provider::Github => ClientConfig {
+            base_url: "https://api.github.com".to_owned(),
+            requirements,
+            download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
+            version,
+            cache: Some(config.package_cache()),
+        },
+        Provider::Local => ClientConfig {
+            base_url: "".to_owned(),
+            requirements,
+            download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
+            version,
+            cache: Some(config.package_cache()),
         },
         Provider::Azul => ClientConfig {
             base_url: "https://api.azul.com/zulu/download/community/v1.0".to_owned(),
             requirements,
             download_dir: config.get_download_dir(),
+            version_req: VersionReq::Matching(version.value.clone()),
             version,
+            cache: Some(config.package_cache()),
+        },
+    };
+    match config.settings.mirror(&provider) {
+        Some(mirror) => ClientConfig {
+            base_url: mirror.trim_end_matches('/').to_owned(),
+            ..client_config
         },
+        None => client_config,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::version;
+    use crate::{loglevel::LogLevel, provider::Provider, version};
 
     use std::time::{Duration, Instant};
     use version::Version;
@@ -236,8 +392,9 @@ mod tests {
         let client = new_client_config(
             &JvcConfig {
                 base_dir: dirs::home_dir(),
-                java_provider: crate::provider::Provider::Azul,
-                log_level: crate::loglevel::LogLevel::Debug,
+                java_provider: Some(crate::provider::Provider::Azul),
+                log_level: Some(crate::loglevel::LogLevel::Debug),
+                ..JvcConfig::default()
             },
             Version::new(10, false, crate::provider::Provider::Azul),
             VersionRequirements::default(),
@@ -252,13 +409,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn create_new_client_for_adoptium_should_be_ok() {
+        let client = new_client_config(
+            &JvcConfig {
+                base_dir: dirs::home_dir(),
+                java_provider: Some(crate::provider::Provider::Adoptium),
+                log_level: Some(crate::loglevel::LogLevel::Debug),
+                ..JvcConfig::default()
+            },
+            Version::new(17, true, crate::provider::Provider::Adoptium),
+            VersionRequirements::default(),
+        );
+        assert_eq!(client.base_url, "https://api.adoptium.net/v3".to_owned());
+        assert_eq!(client.requirements.vendor, None)
+    }
+
     #[test]
     fn create_new_client_for_adoptopenjdk_should_be_ok() {
         let client = new_client_config(
             &JvcConfig {
                 base_dir: dirs::home_dir(),
-                java_provider: crate::provider::Provider::AdoptOpenJDK,
-                log_level: crate::loglevel::LogLevel::Debug,
+                java_provider: Some(crate::provider::Provider::AdoptOpenJDK),
+                log_level: Some(crate::loglevel::LogLevel::Debug),
+                ..JvcConfig::default()
             },
             Version::new(10, false, crate::provider::Provider::AdoptOpenJDK),
             VersionRequirements::default(),
@@ -273,6 +447,72 @@ mod tests {
         )
     }
 
+    #[test]
+    fn command_line_should_take_precedence_over_settings() {
+        let mut config = JvcConfig::default();
+        assert_eq!(config.provider(), Provider::Adoptium);
+        assert_eq!(config.log_level(), LogLevel::Info);
+
+        config.settings.set("provider", "azul").unwrap();
+        config.settings.set("log_level", "error").unwrap();
+        config.settings.set("requirements.arch", "aarch64").unwrap();
+        config
+            .settings
+            .set("requirements.image_type", "jre")
+            .unwrap();
+        assert_eq!(config.provider(), Provider::Azul);
+        assert_eq!(config.log_level(), LogLevel::Error);
+
+        let config = JvcConfig {
+            java_provider: Some(Provider::Disco),
+            ..config
+        };
+        assert_eq!(config.provider(), Provider::Disco);
+        let requirements = config.requirements(VersionRequirements {
+            image_type: Some("jdk".to_owned()),
+            ..VersionRequirements::default()
+        });
+        assert_eq!(requirements.image_type, Some("jdk".to_owned()));
+        assert_eq!(
+            config.requirements(empty_requirements()).arch,
+            Some("aarch64".to_owned())
+        );
+    }
+
+    #[test]
+    fn new_client_config_should_use_mirror() {
+        let mut config = JvcConfig {
+            java_provider: Some(Provider::Adoptium),
+            ..JvcConfig::default()
+        };
+        config
+            .settings
+            .set("mirrors.adoptium", "https://mirror.example.com/v3/")
+            .unwrap();
+        let client = new_client_config(
+            &config,
+            Version::new(17, true, Provider::Adoptium),
+            VersionRequirements::default(),
+        );
+        assert_eq!(client.base_url, "https://mirror.example.com/v3");
+    }
+
+    fn empty_requirements() -> VersionRequirements {
+        VersionRequirements {
+            arch: None,
+            image_type: None,
+            jvm_impl: None,
+            heap_size: None,
+            release_type: None,
+            vendor: None,
+            project: None,
+            os: None,
+            github_repo: None,
+            asset_pattern: None,
+            distribution: None,
+        }
+    }
+
     #[test]
     fn aliases_dir_should_be_ok() {
         let aliases_dir = JvcConfig::default().aliases_dir();
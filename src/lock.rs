@@ -0,0 +1,95 @@
+use fs2::{lock_contended_error, FileExt};
+use log::{debug, info};
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+};
+use thiserror::Error;
+
+pub const LOCK_FILE: &str = "jvc.lock";
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Another jvc process is running. Use --wait to wait until it finishes")]
+    Busy,
+    #[error("Cannot lock {file}: {source}")]
+    Io { file: String, source: io::Error },
+}
+
+/// Advisory lock on `jvc.lock` held by commands that change installed versions, aliases,
+/// downloads or the cache. Released when dropped.
+#[derive(Debug)]
+pub struct StateLock {
+    file: File,
+}
+
+impl StateLock {
+    pub fn acquire<P: AsRef<Path>>(base_dir: P, wait: bool) -> Result<Self, LockError> {
+        let path = base_dir.as_ref().join(LOCK_FILE);
+        let io_error = |source| LockError::Io {
+            file: path.to_string_lossy().into_owned(),
+            source,
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(io_error)?;
+
+        match file.try_lock_exclusive() {
+            Ok(()) => {}
+            Err(e) if e.kind() == lock_contended_error().kind() && wait => {
+                info!("Waiting for another jvc process to finish");
+                file.lock_exclusive().map_err(io_error)?;
+            }
+            Err(e) if e.kind() == lock_contended_error().kind() => return Err(LockError::Busy),
+            Err(e) => return Err(io_error(e)),
+        }
+        debug!("Acquired lock {:?}", path);
+        Ok(StateLock { file })
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.unlock() {
+            debug!("Cannot release lock: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LockError, StateLock};
+    use std::{thread, time::Duration};
+    use tempdir::TempDir;
+
+    #[test]
+    fn second_lock_should_fail_while_first_is_held() {
+        let dir = TempDir::new("jvc-lock").unwrap();
+        let lock = StateLock::acquire(dir.path(), false).unwrap();
+
+        assert!(matches!(
+            StateLock::acquire(dir.path(), false),
+            Err(LockError::Busy)
+        ));
+
+        drop(lock);
+        assert!(StateLock::acquire(dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn wait_should_acquire_lock_once_released() {
+        let dir = TempDir::new("jvc-lock").unwrap();
+        let lock = StateLock::acquire(dir.path(), false).unwrap();
+        let holder = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(lock);
+        });
+
+        assert!(StateLock::acquire(dir.path(), true).is_ok());
+        holder.join().unwrap();
+    }
+}
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use log::LevelFilter;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LogLevel {
     Silent,
     Debug,
@@ -1,10 +1,19 @@
 mod archive;
+mod cache;
+mod checksum;
 mod client;
 mod commands;
 mod config;
+mod lock;
 mod loglevel;
+mod project;
 mod provider;
+mod release;
+mod settings;
 mod shell;
+mod shell_links;
+#[cfg(test)]
+mod test_support;
 mod version;
 
 use std::time::Instant;
@@ -48,6 +57,25 @@ pub enum SubCommand {
     Alias(commands::alias::Alias),
     /// Sets default java version
     Default(commands::default::Default),
+    /// Register JDKs already installed in well-known locations without copying them
+    #[structopt(name="import", visible_aliases= &["discover"])]
+    Import(commands::import::Import),
+    /// Read and change persistent defaults stored in config.toml
+    Config(commands::config::Config),
+    /// Inspect and prune the cache of downloaded packages
+    Cache(commands::cache::Cache),
+    /// Run a command with JAVA_HOME and PATH set for an installed version or alias
+    Exec(commands::exec::Exec),
+    /// Use a version or alias in the current shell, or the one pinned by .jvcrc, .java-version or .sdkmanrc
+    Use(commands::use_version::Use),
+    /// Show the java version used by the current shell
+    Current(commands::current::Current),
+    /// Print the path of a tool like java, javac or jar from the current java version
+    Which(commands::which::Which),
+    /// Check the jvc setup and remove links of closed shells
+    Doctor(commands::doctor::Doctor),
+    /// Print a completion script for your shell
+    Completions(commands::completions::Completions),
     #[cfg(target_os = "windows")]
     /// One time setup for windows env
     Setup(commands::windows::Setup),
@@ -64,6 +92,15 @@ impl SubCommand {
             SubCommand::Package(executor) => executor.execute(config).await,
             SubCommand::Alias(executor) => executor.execute(config).await,
             SubCommand::Default(executor) => executor.execute(config).await,
+            SubCommand::Use(executor) => executor.execute(config).await,
+            SubCommand::Exec(executor) => executor.execute(config).await,
+            SubCommand::Current(executor) => executor.execute(config).await,
+            SubCommand::Which(executor) => executor.execute(config).await,
+            SubCommand::Doctor(executor) => executor.execute(config).await,
+            SubCommand::Completions(executor) => executor.execute(config).await,
+            SubCommand::Import(executor) => executor.execute(config).await,
+            SubCommand::Config(executor) => executor.execute(config).await,
+            SubCommand::Cache(executor) => executor.execute(config).await,
             #[cfg(target_os = "windows")]
             SubCommand::Setup(executor) => executor.execute(config).await,
         }
@@ -82,14 +119,24 @@ async fn main() -> Result<()> {
     let start_time = Instant::now();
 
     let args: Cli = Cli::from_args();
-    let config = args.config;
-
-    let result = config.clean_up_downloads_dir();
-    if let Err(e) = result {
-        warn!("Cannot clean up downloads dir: {}", e)
-    }
+    let config = match args.main_commands {
+        // `jvc config` is how a broken config.toml gets repaired, so it must still run
+        SubCommand::Config(_) => {
+            let (config, error) = args.config.with_settings_lenient();
+            init_logging(&config.log_level());
+            if let Some(e) = error {
+                warn!("{:#}", e);
+            }
+            config
+        }
+        _ => {
+            let config = args.config.with_settings()?;
+            init_logging(&config.log_level());
+            config
+        }
+    };
+    config.settings.apply_proxy();
 
-    init_logging(&config.log_level);
     args.main_commands.execute(config).await?;
 
     let end = start_time.elapsed();
@@ -0,0 +1,137 @@
+use crate::version::VersionReq;
+use anyhow::{Context, Result};
+use log::debug;
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+/// Files that pin a java version for a project, in lookup order.
+pub const PROJECT_FILES: &[&str; 3] = &[".jvcrc", ".java-version", ".sdkmanrc"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectVersion {
+    pub file: PathBuf,
+    pub version: String,
+}
+
+/// Walk up from `start` and return the version pinned by the closest project file.
+pub fn find_project_version<P: AsRef<Path>>(start: P) -> Result<Option<ProjectVersion>> {
+    for dir in start.as_ref().ancestors() {
+        for file_name in PROJECT_FILES {
+            let file = dir.join(file_name);
+            if !file.is_file() {
+                continue;
+            }
+            debug!("Found project file: {:?}", file);
+            let content = read_to_string(&file)
+                .with_context(|| format!("Cannot read project file {:?}", file))?;
+            if let Some(version) = parse_project_file(file_name, &content) {
+                return Ok(Some(ProjectVersion { file, version }));
+            }
+            debug!("Project file {:?} does not pin a java version", file);
+        }
+    }
+    Ok(None)
+}
+
+fn parse_project_file(file_name: &str, content: &str) -> Option<String> {
+    if file_name == ".sdkmanrc" {
+        content
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| line.strip_prefix("java"))
+            .filter_map(|rest| rest.trim_start().strip_prefix('='))
+            .map(|value| strip_vendor_suffix(value.trim()))
+            .find(|value| !value.is_empty())
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_owned)
+    }
+}
+
+/// sdkman identifiers look like `17.0.2-tem`; only the version part is relevant.
+fn strip_vendor_suffix(value: &str) -> String {
+    match value.rfind('-') {
+        Some(index) if !value[index + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+            value[..index].to_owned()
+        }
+        _ => value.to_owned(),
+    }
+}
+
+/// Read values like `17`, `1.8`, `>=11`, `11.0.2` or `temurin-17.0.2` as a version requirement.
+pub fn project_version_req(value: &str) -> Option<VersionReq> {
+    value.parse::<VersionReq>().ok().or_else(|| {
+        let start = value
+            .split('-')
+            .position(|part| part.starts_with(|c: char| c.is_ascii_digit()))?;
+        let version: Vec<&str> = value.split('-').skip(start).collect();
+        version.join("-").parse::<VersionReq>().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_project_version, parse_project_file, project_version_req};
+    use crate::version::VersionReq;
+    use std::fs::{create_dir_all, write};
+    use tempdir::TempDir;
+
+    #[test]
+    fn should_parse_java_version_file() {
+        let version = parse_project_file(".java-version", "\n# pinned\n17.0.2\n");
+        assert_eq!(version, Some("17.0.2".to_owned()));
+    }
+
+    #[test]
+    fn should_parse_sdkmanrc_file() {
+        let content =
+            "# Enable auto-env through the sdkman_auto_env config\njava=17.0.2-tem\nmaven=3.8.4\n";
+        let version = parse_project_file(".sdkmanrc", content);
+        assert_eq!(version, Some("17.0.2".to_owned()));
+    }
+
+    #[test]
+    fn sdkmanrc_without_java_should_be_ignored() {
+        assert_eq!(parse_project_file(".sdkmanrc", "maven=3.8.4\n"), None);
+    }
+
+    #[test]
+    fn should_read_project_version_requirement() {
+        let req = |value: &str| project_version_req(value).map(|req| req.to_string());
+        assert_eq!(req("17"), Some("17".to_owned()));
+        assert_eq!(req("11.0.12"), Some("11.0.12".to_owned()));
+        assert_eq!(req("1.8"), Some("8".to_owned()));
+        assert_eq!(req(">=17"), Some(">=17".to_owned()));
+        assert_eq!(req("temurin-17.0.2"), Some("17.0.2".to_owned()));
+        assert_eq!(req("17-ea"), Some("17-ea".to_owned()));
+        assert_eq!(project_version_req("default"), None::<VersionReq>);
+    }
+
+    #[test]
+    fn should_find_closest_project_file_walking_up() {
+        let root = TempDir::new("jvc-project").unwrap();
+        let nested = root.path().join("module").join("src");
+        create_dir_all(&nested).unwrap();
+        write(root.path().join(".java-version"), "11\n").unwrap();
+        write(root.path().join("module").join(".jvcrc"), "17\n").unwrap();
+
+        let project = find_project_version(&nested).unwrap().unwrap();
+        assert_eq!(project.version, "17");
+        assert_eq!(project.file, root.path().join("module").join(".jvcrc"));
+    }
+
+    #[test]
+    fn should_prefer_jvcrc_in_same_directory() {
+        let root = TempDir::new("jvc-project").unwrap();
+        write(root.path().join(".java-version"), "11\n").unwrap();
+        write(root.path().join(".jvcrc"), "work\n").unwrap();
+
+        let project = find_project_version(root.path()).unwrap().unwrap();
+        assert_eq!(project.version, "work");
+    }
+}
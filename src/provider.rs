@@ -1,18 +1,40 @@
 use anyhow::anyhow;
-use serde::Deserialize;
+use log::warn;
+use serde::{Deserialize, Serialize, Serializer};
 use std::{fmt::Display, str::FromStr};
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Provider {
+    Adoptium,
     AdoptOpenJDK,
     Azul,
+    Disco,
+    Github,
+    /// Versions installed from a local archive or directory
+    Local,
 }
 
 impl Provider {
     pub fn as_str(&self) -> String {
         match self {
+            Provider::Adoptium => "adoptium".to_owned(),
             Provider::AdoptOpenJDK => "adoptopenjdk".to_owned(),
             Provider::Azul => "azul".to_owned(),
+            Provider::Disco => "disco".to_owned(),
+            Provider::Github => "github".to_owned(),
+            Provider::Local => "local".to_owned(),
+        }
+    }
+
+    /// The AdoptOpenJDK API is retired, its releases continue as Eclipse Adoptium.
+    /// Versions already installed from AdoptOpenJDK keep their provider.
+    pub fn migrate(self) -> Provider {
+        match self {
+            Provider::AdoptOpenJDK => {
+                warn!("AdoptOpenJDK moved to Eclipse Adoptium, using provider adoptium instead");
+                Provider::Adoptium
+            }
+            provider => provider,
         }
     }
 }
@@ -22,13 +44,23 @@ impl FromStr for Provider {
 
     fn from_str(provider_name: &str) -> Result<Self, Self::Err> {
         match provider_name {
+            "adoptium" | "temurin" => Ok(Provider::Adoptium),
             "adoptopenjdk" => Ok(Provider::AdoptOpenJDK),
             "azul" => Ok(Provider::Azul),
+            "disco" | "foojay" => Ok(Provider::Disco),
+            "github" => Ok(Provider::Github),
+            "local" => Ok(Provider::Local),
             _ => Err(anyhow!("Cannot parse provider name {}", provider_name)),
         }
     }
 }
 
+impl Serialize for Provider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.as_str())
+    }
+}
+
 impl Display for Provider {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -0,0 +1,133 @@
+use crate::{provider::Provider, version::JavaVersion};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+/// Information read from the `release` file at the root of a JDK.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JdkRelease {
+    pub version: JavaVersion,
+    pub implementor: Option<String>,
+}
+
+impl JdkRelease {
+    pub fn read<P: AsRef<Path>>(java_home: P) -> Result<Self> {
+        let release_file = java_home.as_ref().join("release");
+        let content = read_to_string(&release_file)
+            .with_context(|| format!("Cannot read JDK release file {:?}", release_file))?;
+        Self::parse(&content)
+    }
+
+    /// Read `KEY="value"` lines. `JAVA_RUNTIME_VERSION` is preferred as it contains the build number.
+    pub fn parse(content: &str) -> Result<Self> {
+        let values: HashMap<&str, &str> = content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+            .collect();
+
+        let version: JavaVersion = values
+            .get("JAVA_VERSION")
+            .ok_or_else(|| anyhow!("JDK release file does not contain JAVA_VERSION"))?
+            .parse()?;
+        let runtime_version = values
+            .get("JAVA_RUNTIME_VERSION")
+            .and_then(|v| v.parse::<JavaVersion>().ok())
+            .filter(|runtime| version.is_prefix_of(runtime));
+
+        Ok(Self {
+            version: runtime_version.unwrap_or(version),
+            implementor: values
+                .get("IMPLEMENTOR")
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string()),
+        })
+    }
+
+    /// Provider and distribution matching the implementor. Unknown implementors are `local`.
+    pub fn provider(&self) -> (Provider, Option<String>) {
+        let implementor = self.implementor.as_deref().unwrap_or_default();
+        let distribution = |name: &str| (Provider::Local, Some(name.to_owned()));
+        match implementor {
+            "Eclipse Adoptium" => (Provider::Adoptium, None),
+            "AdoptOpenJDK" => (Provider::AdoptOpenJDK, None),
+            "Azul Systems, Inc." => (Provider::Azul, None),
+            "Amazon.com Inc." => distribution("corretto"),
+            "BellSoft" => distribution("liberica"),
+            "SAP SE" => distribution("sap_machine"),
+            "Microsoft" => distribution("microsoft"),
+            "GraalVM Community" => distribution("graalvm_ce"),
+            "IBM Corporation" | "International Business Machines Corporation" => {
+                distribution("semeru")
+            }
+            "Oracle Corporation" => distribution("oracle"),
+            _ => (Provider::Local, None),
+        }
+    }
+}
+
+/// Locate the JDK root inside `dir`, including the macOS `Contents/Home` bundle layout.
+pub fn find_java_home<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+    let dir = dir.as_ref();
+    [dir.to_owned(), dir.join("Contents").join("Home")]
+        .iter()
+        .find(|home| home.join("release").is_file())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_java_home, JdkRelease};
+    use crate::provider::Provider;
+    use std::fs::{create_dir_all, write};
+    use tempdir::TempDir;
+
+    const TEMURIN_RELEASE: &str = r#"IMPLEMENTOR="Eclipse Adoptium"
+IMPLEMENTOR_VERSION="Temurin-17.0.2+8"
+JAVA_VERSION="17.0.2"
+JAVA_VERSION_DATE="2022-01-18"
+JAVA_RUNTIME_VERSION="17.0.2+8"
+MODULES="java.base java.compiler"
+OS_ARCH="x86_64"
+OS_NAME="Linux"
+"#;
+
+    #[test]
+    fn should_read_version_and_implementor() {
+        let release = JdkRelease::parse(TEMURIN_RELEASE).unwrap();
+        assert_eq!(release.version.to_string(), "17.0.2+8");
+        assert_eq!(release.implementor, Some("Eclipse Adoptium".to_owned()));
+        assert_eq!(release.provider(), (Provider::Adoptium, None));
+    }
+
+    #[test]
+    fn should_read_legacy_release_without_runtime_version() {
+        let release =
+            JdkRelease::parse("JAVA_VERSION=\"1.8.0_322\"\nIMPLEMENTOR=\"Amazon.com Inc.\"\n")
+                .unwrap();
+        assert_eq!(release.version.to_string(), "8.0.322");
+        assert_eq!(
+            release.provider(),
+            (Provider::Local, Some("corretto".to_owned()))
+        );
+    }
+
+    #[test]
+    fn should_fail_without_java_version() {
+        assert!(JdkRelease::parse("IMPLEMENTOR=\"Eclipse Adoptium\"\n").is_err());
+    }
+
+    #[test]
+    fn should_find_java_home_in_macos_bundle() {
+        let dir = TempDir::new("jvc-release").unwrap();
+        let home = dir.path().join("Contents").join("Home");
+        create_dir_all(&home).unwrap();
+        assert_eq!(find_java_home(dir.path()), None);
+
+        write(home.join("release"), TEMURIN_RELEASE).unwrap();
+        assert_eq!(find_java_home(dir.path()), Some(home));
+    }
+}
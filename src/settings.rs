@@ -0,0 +1,408 @@
+use crate::{
+    cache::parse_size, config::VersionRequirements, loglevel::LogLevel, provider::Provider,
+};
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{read_to_string, rename, write},
+    path::Path,
+};
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Persistent defaults read from `config.toml` in the jvc root directory.
+///
+/// Values given as command line options or `JVC_*` environment variables take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    pub provider: Option<String>,
+    pub log_level: Option<String>,
+    #[serde(default)]
+    pub requirements: RequirementSettings,
+    /// Base urls replacing the provider APIs, keyed by provider name
+    #[serde(default)]
+    pub mirrors: BTreeMap<String, String>,
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementSettings {
+    pub arch: Option<String>,
+    pub image_type: Option<String>,
+    pub jvm_impl: Option<String>,
+    pub heap_size: Option<String>,
+    pub os: Option<String>,
+    pub vendor: Option<String>,
+    pub distribution: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxySettings {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheSettings {
+    /// Size like `2G` above which least recently used packages are removed
+    pub max_size: Option<String>,
+}
+
+impl Settings {
+    /// Read settings from `path`. A missing file means no settings.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let settings = Settings::load_unchecked(path)?;
+        for (key, value) in settings.entries() {
+            validate(&key, &value).with_context(|| format!("Invalid config file {:?}", path))?;
+        }
+        Ok(settings)
+    }
+
+    /// Parse config.toml without validating the values, so `jvc config` can repair them.
+    pub fn load_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+        let content =
+            read_to_string(path).with_context(|| format!("Cannot read config file {:?}", path))?;
+        toml::from_str(&content).with_context(|| format!("Cannot parse config file {:?}", path))
+    }
+
+    /// Write settings through a temporary file so a failed write keeps the previous file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("toml.tmp");
+        write(&tmp_path, toml::to_string(self)?)?;
+        rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        if let Some(provider) = key.strip_prefix("mirrors.") {
+            return Ok(self.mirrors.get(&mirror_key(provider)?).cloned());
+        }
+        Ok(self.field(key)?.clone())
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        validate(key, value)?;
+        if let Some(provider) = key.strip_prefix("mirrors.") {
+            self.mirrors.insert(mirror_key(provider)?, value.to_owned());
+        } else {
+            *self.field_mut(key)? = Some(value.to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        if let Some(provider) = key.strip_prefix("mirrors.") {
+            self.mirrors.remove(&mirror_key(provider)?);
+        } else {
+            *self.field_mut(key)? = None;
+        }
+        Ok(())
+    }
+
+    /// All configured values as `key = value` pairs, in file order.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<(String, String)> = KEYS
+            .iter()
+            .filter_map(|key| {
+                self.get(key)
+                    .ok()
+                    .flatten()
+                    .map(|value| (key.to_string(), value))
+            })
+            .collect();
+        entries.extend(
+            self.mirrors
+                .iter()
+                .map(|(provider, url)| (format!("mirrors.{}", provider), url.clone())),
+        );
+        entries
+    }
+
+    pub fn provider(&self) -> Option<Provider> {
+        self.provider.as_deref().and_then(|p| p.parse().ok())
+    }
+
+    pub fn log_level(&self) -> Option<LogLevel> {
+        self.log_level.as_deref().and_then(|l| l.parse().ok())
+    }
+
+    pub fn cache_max_size(&self) -> Option<u64> {
+        let max_size = self.cache.max_size.as_deref()?;
+        match parse_size(max_size) {
+            Ok(size) => Some(size),
+            Err(e) => {
+                warn!("Ignore setting cache.max_size: {:#}", e);
+                None
+            }
+        }
+    }
+
+    pub fn mirror(&self, provider: &Provider) -> Option<&str> {
+        self.mirrors.get(&provider.as_str()).map(String::as_str)
+    }
+
+    pub fn requirements(&self) -> VersionRequirements {
+        let requirements = self.requirements.clone();
+        VersionRequirements {
+            arch: requirements.arch,
+            image_type: requirements.image_type,
+            jvm_impl: requirements.jvm_impl,
+            heap_size: requirements.heap_size,
+            os: requirements.os,
+            vendor: requirements.vendor,
+            distribution: requirements.distribution,
+            release_type: None,
+            project: None,
+            github_repo: None,
+            asset_pattern: None,
+        }
+    }
+
+    /// Export proxy settings for the http client unless the environment already defines them.
+    pub fn apply_proxy(&self) {
+        let proxies = [
+            ("HTTP_PROXY", &self.proxy.http),
+            ("HTTPS_PROXY", &self.proxy.https),
+            ("NO_PROXY", &self.proxy.no_proxy),
+        ];
+        for (name, value) in proxies.iter() {
+            let is_set = env::var_os(name).is_some() || env::var_os(name.to_lowercase()).is_some();
+            if let (Some(value), false) = (value, is_set) {
+                env::set_var(name, value);
+            }
+        }
+    }
+
+    fn field(&self, key: &str) -> Result<&Option<String>> {
+        Ok(match key {
+            "provider" => &self.provider,
+            "log_level" => &self.log_level,
+            "requirements.arch" => &self.requirements.arch,
+            "requirements.image_type" => &self.requirements.image_type,
+            "requirements.jvm_impl" => &self.requirements.jvm_impl,
+            "requirements.heap_size" => &self.requirements.heap_size,
+            "requirements.os" => &self.requirements.os,
+            "requirements.vendor" => &self.requirements.vendor,
+            "requirements.distribution" => &self.requirements.distribution,
+            "proxy.http" => &self.proxy.http,
+            "proxy.https" => &self.proxy.https,
+            "proxy.no_proxy" => &self.proxy.no_proxy,
+            "cache.max_size" => &self.cache.max_size,
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    fn field_mut(&mut self, key: &str) -> Result<&mut Option<String>> {
+        Ok(match key {
+            "provider" => &mut self.provider,
+            "log_level" => &mut self.log_level,
+            "requirements.arch" => &mut self.requirements.arch,
+            "requirements.image_type" => &mut self.requirements.image_type,
+            "requirements.jvm_impl" => &mut self.requirements.jvm_impl,
+            "requirements.heap_size" => &mut self.requirements.heap_size,
+            "requirements.os" => &mut self.requirements.os,
+            "requirements.vendor" => &mut self.requirements.vendor,
+            "requirements.distribution" => &mut self.requirements.distribution,
+            "proxy.http" => &mut self.proxy.http,
+            "proxy.https" => &mut self.proxy.https,
+            "proxy.no_proxy" => &mut self.proxy.no_proxy,
+            "cache.max_size" => &mut self.cache.max_size,
+            _ => return Err(unknown_key(key)),
+        })
+    }
+}
+
+const KEYS: &[&str] = &[
+    "provider",
+    "log_level",
+    "requirements.arch",
+    "requirements.image_type",
+    "requirements.jvm_impl",
+    "requirements.heap_size",
+    "requirements.os",
+    "requirements.vendor",
+    "requirements.distribution",
+    "proxy.http",
+    "proxy.https",
+    "proxy.no_proxy",
+    "cache.max_size",
+];
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown config key {}. Possible keys: {}, mirrors.<provider>",
+        key,
+        KEYS.join(", ")
+    )
+}
+
+/// Name mirrors are stored under, so aliases like `temurin` reach the provider they stand for.
+fn mirror_key(provider: &str) -> Result<String> {
+    Ok(provider.parse::<Provider>()?.migrate().as_str())
+}
+
+fn validate(key: &str, value: &str) -> Result<()> {
+    match key {
+        "provider" => {
+            value.parse::<Provider>()?;
+        }
+        "log_level" => {
+            value.parse::<LogLevel>().map_err(|e| anyhow!(e))?;
+        }
+        "cache.max_size" => {
+            parse_size(value)?;
+        }
+        "proxy.http" | "proxy.https" => {
+            Url::parse(value).with_context(|| format!("Invalid proxy url {}", value))?;
+        }
+        _ if key.starts_with("mirrors.") => {
+            key["mirrors.".len()..].parse::<Provider>()?;
+            Url::parse(value).with_context(|| format!("Invalid mirror url {}", value))?;
+        }
+        _ if KEYS.contains(&key) => {}
+        _ => return Err(unknown_key(key)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+    use crate::{loglevel::LogLevel, provider::Provider};
+    use std::fs::write;
+    use tempdir::TempDir;
+
+    #[test]
+    fn load_should_read_all_tables() {
+        let dir = TempDir::new("jvc-settings").unwrap();
+        let path = dir.path().join("config.toml");
+        write(
+            &path,
+            r#"provider = "azul"
+log_level = "debug"
+
+[requirements]
+arch = "aarch64"
+jvm_impl = "openj9"
+
+[mirrors]
+adoptium = "https://mirror.example.com/adoptium/v3"
+
+[proxy]
+https = "http://proxy.example.com:3128"
+"#,
+        )
+        .unwrap();
+
+        let settings = Settings::load(&path).unwrap();
+
+        assert_eq!(settings.provider(), Some(Provider::Azul));
+        assert_eq!(settings.log_level(), Some(LogLevel::Debug));
+        assert_eq!(settings.requirements().arch, Some("aarch64".to_owned()));
+        assert_eq!(settings.requirements().image_type, None);
+        assert_eq!(
+            settings.mirror(&Provider::Adoptium),
+            Some("https://mirror.example.com/adoptium/v3")
+        );
+        assert_eq!(
+            settings.get("proxy.https").unwrap(),
+            Some("http://proxy.example.com:3128".to_owned())
+        );
+    }
+
+    #[test]
+    fn load_should_accept_missing_file_and_reject_invalid_values() {
+        let dir = TempDir::new("jvc-settings").unwrap();
+        let path = dir.path().join("config.toml");
+        assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+        write(&path, "provider = \"unknown\"\n").unwrap();
+        assert!(Settings::load(&path).is_err());
+    }
+
+    #[test]
+    fn set_should_validate_and_save_should_roundtrip() {
+        let dir = TempDir::new("jvc-settings").unwrap();
+        let path = dir.path().join("config.toml");
+        let mut settings = Settings::default();
+
+        settings.set("provider", "disco").unwrap();
+        settings
+            .set("requirements.distribution", "corretto")
+            .unwrap();
+        settings
+            .set("mirrors.github", "https://github.example.com/api")
+            .unwrap();
+        assert!(settings.set("provider", "unknown").is_err());
+        assert!(settings.set("log_level", "verbose").is_err());
+        assert!(settings.set("mirrors.adoptium", "not a url").is_err());
+        assert!(settings.set("unknown", "value").is_err());
+        settings.save(&path).unwrap();
+
+        let loaded = Settings::load(&path).unwrap();
+        assert_eq!(loaded, settings);
+        assert_eq!(
+            loaded.entries(),
+            vec![
+                ("provider".to_owned(), "disco".to_owned()),
+                (
+                    "requirements.distribution".to_owned(),
+                    "corretto".to_owned()
+                ),
+                (
+                    "mirrors.github".to_owned(),
+                    "https://github.example.com/api".to_owned()
+                ),
+            ]
+        );
+
+        settings.unset("mirrors.github").unwrap();
+        settings.unset("provider").unwrap();
+        assert_eq!(settings.get("provider").unwrap(), None);
+        assert_eq!(settings.entries().len(), 1);
+    }
+
+    #[test]
+    fn mirror_should_be_stored_under_the_provider_of_an_alias() {
+        let mut settings = Settings::default();
+
+        settings
+            .set("mirrors.temurin", "https://mirror.example.com/adoptium/v3")
+            .unwrap();
+        assert_eq!(
+            settings.mirror(&Provider::Adoptium),
+            Some("https://mirror.example.com/adoptium/v3")
+        );
+        assert_eq!(
+            settings.get("mirrors.adoptopenjdk").unwrap().as_deref(),
+            Some("https://mirror.example.com/adoptium/v3")
+        );
+
+        settings.unset("mirrors.temurin").unwrap();
+        assert_eq!(settings.mirror(&Provider::Adoptium), None);
+    }
+
+    #[test]
+    fn invalid_cache_max_size_should_be_ignored() {
+        let mut settings = Settings::default();
+        settings.cache.max_size = Some("18446744073709551615G".to_owned());
+        assert_eq!(settings.cache_max_size(), None);
+
+        settings.set("cache.max_size", "2G").unwrap();
+        assert_eq!(settings.cache_max_size(), Some(2 * 1024 * 1024 * 1024));
+    }
+}
@@ -1,28 +1,53 @@
-use std::path::PathBuf;
-
-use super::shell::Shell;
+use super::{quote, shell::Shell};
 use async_trait::async_trait;
-use log::warn;
+use std::{ffi::OsStr, path::Path};
 
 #[derive(Debug)]
 pub struct Bash;
 
 #[async_trait]
 impl Shell for Bash {
-    async fn into_clap_shell(&self) -> structopt::clap::Shell {
-        structopt::clap::Shell::Bash
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        Some(structopt::clap::Shell::Bash)
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        format!("export PATH={}:\"$PATH\"", self.quote(path.as_os_str()))
     }
 
-    async fn export_path(&self, path: &PathBuf) -> String {
-        if let Some(new_path) = path.to_str() {
-            format!("export PATH={:?}:$PATH", new_path)
-        } else {
-            warn!("Cannot construct path for Path variable");
-            "".to_owned()
-        }
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("export {}={}", name, self.quote(value))
     }
 
-    async fn set_env_var(&self, name: &str, value: &str) -> String {
-        format!("export {}={:?}", name, value)
+    fn quote(&self, value: &OsStr) -> String {
+        quote::posix(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"__jvc_use_if_file_found() {
+    jvc use --quiet
+}
+
+__jvc_cd() {
+    \cd "$@" || return $?
+    __jvc_use_if_file_found
+}
+
+alias cd=__jvc_cd
+__jvc_use_if_file_found"#
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bash;
+    use crate::shell::shell::tests::render_env;
+
+    #[tokio::test]
+    async fn env_script_should_match_golden_file() {
+        assert_eq!(render_env(&Bash).await, include_str!("fixtures/bash.txt"));
     }
 }
@@ -1,79 +1,189 @@
 #![cfg(unix)]
 
 use std::{
-    io::{BufRead, BufReader, Error, ErrorKind, Result},
+    collections::HashSet,
+    env,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
     process,
 };
 
 use log::debug;
-use process::Stdio;
 
-use crate::shell::{bash::Bash, powershell::PowerShell, shell::Shell, zsh::Zsh};
+use crate::shell::{
+    bash::Bash, elvish::Elvish, fish::Fish, nushell::Nushell, powershell::PowerShell, shell::Shell,
+    xonsh::Xonsh, zsh::Zsh,
+};
 
 #[derive(Debug)]
 struct ProcInfo {
     parent_pid: Option<u32>,
     command: String,
+    /// Binary of the process, differs from the command for scripts like xonsh run by python
+    executable: Option<PathBuf>,
 }
 
+/// Closest shell in the process tree, otherwise the login shell from `$SHELL`.
 pub fn detect_shell() -> Option<Box<dyn Shell>> {
+    detect_shell_process()
+        .map(|(_, shell)| shell)
+        .or_else(login_shell)
+}
+
+/// Pid of the shell running jvc. Subshells, like the one of `$(jvc env)`, are skipped so the pid
+/// belongs to the interactive shell.
+pub fn shell_pid() -> Option<u32> {
+    let (mut pid, _) = detect_shell_process()?;
+    let shell_binary = binary(&get_proc_info(pid).ok()?.command).to_owned();
+    let mut visited = 0u8;
+    while visited < 10 {
+        let parent = match get_proc_info(pid).ok().and_then(|info| info.parent_pid) {
+            Some(parent) => parent,
+            None => break,
+        };
+        match get_proc_info(parent) {
+            Ok(info) if binary(&info.command) == shell_binary => pid = parent,
+            _ => break,
+        }
+        visited += 1;
+    }
+    Some(pid)
+}
+
+/// Pids from `pids` that are still running. All of them are kept when processes cannot be
+/// inspected.
+pub fn alive_pids(pids: &[u32]) -> HashSet<u32> {
+    if get_proc_info(process::id()).is_err() {
+        debug!("Cannot inspect processes, consider all shells alive");
+        return pids.iter().copied().collect();
+    }
+    pids.iter()
+        .copied()
+        .filter(|pid| get_proc_info(*pid).is_ok())
+        .collect()
+}
+
+fn detect_shell_process() -> Option<(u32, Box<dyn Shell>)> {
     let mut pid = Some(process::id());
     let mut visited = 0u8;
 
-    while pid != None && visited < 10 {
-        let proc_info: ProcInfo = get_proc_info(pid.unwrap()).ok()?;
-        let binary = proc_info
-            .command
-            .trim_start_matches('-')
-            .split('/')
-            .last()
-            .expect("Cannot read file name of process tree");
-        match binary {
-            "sh" | "bash" => return Some(Box::new(Bash)),
-            "zsh" => return Some(Box::new(Zsh)),
-            "pwsh" => return Some(Box::new(PowerShell)),
-            cmd => debug!("binary is not a supported shell {:?}", cmd),
+    while let Some(current) = pid {
+        if visited >= 10 {
+            break;
+        }
+        let proc_info = match get_proc_info(current) {
+            Ok(proc_info) => proc_info,
+            Err(e) => {
+                debug!("Cannot inspect process {}: {}", current, e);
+                return None;
+            }
+        };
+        match shell_of_process(&proc_info) {
+            Some(shell) => return Some((current, shell)),
+            None => debug!("binary is not a supported shell {:?}", proc_info.command),
         }
         pid = proc_info.parent_pid;
-        visited = visited + 1;
+        visited += 1;
     }
 
     None
 }
 
+fn login_shell() -> Option<Box<dyn Shell>> {
+    let shell = env::var("SHELL").ok()?;
+    debug!("Use login shell {:?}", shell);
+    shell_of(&shell)
+}
+
+fn shell_of_process(proc_info: &ProcInfo) -> Option<Box<dyn Shell>> {
+    proc_info
+        .executable
+        .as_deref()
+        .and_then(Path::to_str)
+        .and_then(shell_of)
+        .or_else(|| shell_of(&proc_info.command))
+}
+
+fn shell_of(command: &str) -> Option<Box<dyn Shell>> {
+    match binary(command) {
+        "sh" | "bash" => Some(Box::new(Bash)),
+        "zsh" => Some(Box::new(Zsh)),
+        "fish" => Some(Box::new(Fish)),
+        "nu" => Some(Box::new(Nushell)),
+        "elvish" => Some(Box::new(Elvish)),
+        "xonsh" => Some(Box::new(Xonsh)),
+        "pwsh" => Some(Box::new(PowerShell)),
+        _ => None,
+    }
+}
+
+fn binary(command: &str) -> &str {
+    let command = command.trim_start_matches('-');
+    command.rsplit('/').next().unwrap_or(command)
+}
+
+#[cfg(target_os = "linux")]
 fn get_proc_info(pid: u32) -> Result<ProcInfo> {
-    use std::process::Command;
+    use std::fs::{read_link, read_to_string};
 
-    let stream = Command::new("ps")
-        .arg("-o")
-        .arg("ppid,comm")
-        .arg(pid.to_string())
-        .stdout(Stdio::piped())
-        .spawn()?
-        .stdout
-        .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+    let proc_dir = Path::new("/proc").join(pid.to_string());
+    let (command, parent_pid) = parse_stat(&read_to_string(proc_dir.join("stat"))?)?;
+    Ok(ProcInfo {
+        parent_pid,
+        command,
+        // not readable for processes of other users
+        executable: read_link(proc_dir.join("exe")).ok(),
+    })
+}
 
-    let mut output_buf_reader = BufReader::new(stream).lines();
+/// Command and parent pid from `/proc/<pid>/stat`, formatted as `pid (command) state ppid ...`.
+/// The command may contain spaces and parentheses itself.
+#[cfg(target_os = "linux")]
+fn parse_stat(stat: &str) -> Result<(String, Option<u32>)> {
+    let invalid = || Error::new(ErrorKind::InvalidData, format!("Cannot parse {:?}", stat));
+    let start = stat.find('(').ok_or_else(invalid)?;
+    let end = stat.rfind(')').ok_or_else(invalid)?;
+    let command = stat.get(start + 1..end).ok_or_else(invalid)?;
+    let ppid = stat[end + 1..]
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(|_| invalid())?;
+    // pid 0 is the kernel, the parent of init
+    Ok((command.to_owned(), Some(ppid).filter(|ppid| *ppid != 0)))
+}
 
-    // skip next line
-    output_buf_reader
-        .next()
-        .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))??;
+#[cfg(not(target_os = "linux"))]
+fn get_proc_info(pid: u32) -> Result<ProcInfo> {
+    use std::process::Command;
 
-    let line = output_buf_reader
-        .next()
-        .ok_or_else(|| Error::from(ErrorKind::NotFound))??;
+    let output = Command::new("ps")
+        .arg("-o")
+        .arg("ppid=,comm=")
+        .arg("-p")
+        .arg(pid.to_string())
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::from(ErrorKind::NotFound));
+    }
 
-    let mut parts = line.trim().split_whitespace();
-    let ppid = parts
-        .next()
-        .expect("Can't read the ppid from ps. Expected pid to be first item in table.");
-    let command = parts
+    let output = String::from_utf8_lossy(&output.stdout);
+    let line = output
+        .lines()
         .next()
-        .expect("Can't read the ppid from ps. Expected command to be second item in table");
+        .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+    // the command is the last column and may contain spaces
+    let (ppid, command) = line.trim().split_once(char::is_whitespace).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Cannot read ppid and command from ps: {:?}", line),
+        )
+    })?;
     Ok(ProcInfo {
-        parent_pid: u32::from_str_radix(ppid, 10).ok(),
-        command: command.into(),
+        parent_pid: ppid.parse().ok(),
+        command: command.trim().to_owned(),
+        executable: None,
     })
 }
 
@@ -84,14 +194,77 @@ mod tests {
 
     #[test]
     fn test_get_proc_info() {
-        let subprocess = Command::new("bash")
+        let mut subprocess = Command::new("bash")
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
             .expect("Can't execute command");
         let process_info = get_proc_info(subprocess.id());
+        subprocess.kill().unwrap();
+        subprocess.wait().unwrap();
         let parent_pid = process_info.ok().and_then(|x| x.parent_pid);
         assert_eq!(parent_pid, Some(std::process::id()));
     }
+
+    /// Spawn every supported shell found in `PATH` and detect it from its process.
+    #[test]
+    fn spawned_shells_should_be_detected() {
+        let shells = [
+            ("sh", "Bash"),
+            ("bash", "Bash"),
+            ("zsh", "Zsh"),
+            ("fish", "Fish"),
+            ("nu", "Nushell"),
+            ("elvish", "Elvish"),
+            ("xonsh", "Xonsh"),
+            ("pwsh", "PowerShell"),
+        ];
+        let paths = env::var_os("PATH").unwrap_or_default();
+        for (binary, expected) in shells.iter() {
+            // shells which are not installed are skipped
+            if !env::split_paths(&paths).any(|dir| dir.join(binary).is_file()) {
+                continue;
+            }
+            // shells wait for commands on the piped stdin
+            let mut subprocess = Command::new(binary)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("Can't execute shell");
+            let shell = get_proc_info(subprocess.id())
+                .ok()
+                .and_then(|info| shell_of_process(&info));
+            subprocess.kill().unwrap();
+            subprocess.wait().unwrap();
+
+            assert_eq!(
+                shell.map(|shell| format!("{:?}", shell)).as_deref(),
+                Some(*expected),
+                "Cannot detect {} from its process",
+                binary
+            );
+        }
+    }
+
+    #[test]
+    fn login_shell_commands_should_be_recognized() {
+        assert!(shell_of("-zsh").is_some());
+        assert!(shell_of("/usr/local/bin/fish").is_some());
+        assert!(shell_of("python3").is_none());
+        assert_eq!(binary(""), "");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stat_should_be_parsed_with_spaces_in_command() {
+        let (command, ppid) = parse_stat("42 (my (odd) sh) S 7 42 42 0 -1").unwrap();
+        assert_eq!(command, "my (odd) sh");
+        assert_eq!(ppid, Some(7));
+
+        let (_, ppid) = parse_stat("1 (init) S 0 1 1 0 -1").unwrap();
+        assert_eq!(ppid, None);
+        assert!(parse_stat("42 sh S").is_err());
+    }
 }
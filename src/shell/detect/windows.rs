@@ -1,7 +1,7 @@
 #![cfg(windows)]
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{Error, ErrorKind, Result},
     path::PathBuf,
     process::{self, Command, Stdio},
@@ -21,21 +21,62 @@ pub struct ProcInfo {
 }
 
 pub fn detect_shell() -> Option<Box<dyn Shell>> {
+    detect_shell_process().map(|(_, shell)| shell)
+}
+
+/// Pid of the shell running jvc, skipping nested processes of the same shell.
+pub fn shell_pid() -> Option<u32> {
+    let proc_map = get_proc_map().ok()?;
+    let proc_tree = get_proc_tree(proc_map, process::id());
+    let (index, _) = proc_tree
+        .iter()
+        .enumerate()
+        .find(|(_, process)| shell_of(process).is_some())?;
+    let shell_binary = binary(&proc_tree[index]);
+    proc_tree[index..]
+        .iter()
+        .take_while(|process| binary(process) == shell_binary)
+        .last()
+        .map(|process| process.pid)
+}
+
+/// Pids from `pids` that are still running. All of them are kept when wmic cannot be used.
+pub fn alive_pids(pids: &[u32]) -> HashSet<u32> {
+    match get_proc_map() {
+        Ok(proc_map) => pids
+            .iter()
+            .copied()
+            .filter(|pid| proc_map.contains_key(pid))
+            .collect(),
+        Err(_) => pids.iter().copied().collect(),
+    }
+}
+
+fn detect_shell_process() -> Option<(u32, Box<dyn Shell>)> {
     let proc_map = get_proc_map().ok()?;
     let proc_tree = get_proc_tree(proc_map, process::id());
 
-    for process in proc_tree {
-        if let Some(exec_path) = process.exec_path {
-            match exec_path.file_name().and_then(|x| x.to_str()) {
-                Some("cmd.exe") => return Some(Box::from(WindowsCMD)),
-                Some("bash.exe") => return Some(Box::new(Bash)),
-                Some("powershell.exe") | Some("pwsh.exe") => return Some(Box::from(PowerShell)),
-                _ => {}
-            }
-        }
+    proc_tree
+        .iter()
+        .find_map(|process| shell_of(process).map(|shell| (process.pid, shell)))
+}
+
+fn shell_of(process: &ProcInfo) -> Option<Box<dyn Shell>> {
+    match binary(process).as_deref() {
+        Some("cmd.exe") => Some(Box::from(WindowsCMD)),
+        Some("bash.exe") => Some(Box::new(Bash)),
+        Some("powershell.exe") | Some("pwsh.exe") => Some(Box::from(PowerShell)),
+        _ => None,
     }
+}
 
-    None
+fn binary(process: &ProcInfo) -> Option<String> {
+    process
+        .exec_path
+        .as_ref()
+        .and_then(|exec_path| exec_path.file_name())
+        .and_then(|x| x.to_str())
+        .map(str::to_owned)
 }
 
 type ProcessMap = HashMap<u32, ProcInfo>;
@@ -0,0 +1,47 @@
+use super::{quote, shell::Shell};
+use async_trait::async_trait;
+use std::{ffi::OsStr, path::Path};
+
+#[derive(Debug)]
+pub struct Elvish;
+
+#[async_trait]
+impl Shell for Elvish {
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        Some(structopt::clap::Shell::Elvish)
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        format!("set paths = [{} $@paths]", self.quote(path.as_os_str()))
+    }
+
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("set-env {} {}", name, self.quote(value))
+    }
+
+    fn quote(&self, value: &OsStr) -> String {
+        quote::elvish(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"set after-chdir = [$@after-chdir {|_| jvc use --quiet }]
+jvc use --quiet"#
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Elvish;
+    use crate::shell::shell::tests::render_env;
+
+    #[tokio::test]
+    async fn env_script_should_match_golden_file() {
+        assert_eq!(
+            render_env(&Elvish).await,
+            include_str!("fixtures/elvish.txt")
+        );
+    }
+}
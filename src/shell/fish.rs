@@ -0,0 +1,48 @@
+use super::{quote, shell::Shell};
+use async_trait::async_trait;
+use std::{ffi::OsStr, path::Path};
+
+#[derive(Debug)]
+pub struct Fish;
+
+#[async_trait]
+impl Shell for Fish {
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        Some(structopt::clap::Shell::Fish)
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        format!("set -gx PATH {} $PATH", self.quote(path.as_os_str()))
+    }
+
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("set -gx {} {}", name, self.quote(value))
+    }
+
+    fn quote(&self, value: &OsStr) -> String {
+        quote::fish(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"function __jvc_use_on_cd --on-variable PWD --description 'Run jvc use on directory change'
+    status --is-command-substitution; and return
+    jvc use --quiet
+end
+
+__jvc_use_on_cd"#
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fish;
+    use crate::shell::shell::tests::render_env;
+
+    #[tokio::test]
+    async fn env_script_should_match_golden_file() {
+        assert_eq!(render_env(&Fish).await, include_str!("fixtures/fish.txt"));
+    }
+}
@@ -1,18 +1,69 @@
+use std::{collections::HashSet, env};
+
+use anyhow::{anyhow, Result};
+
 use self::shell::Shell;
 
 pub mod bash;
 mod detect;
+pub mod elvish;
+pub mod fish;
+pub mod nushell;
 pub mod powershell;
+pub mod quote;
 pub mod shell;
 pub mod windows_cmd;
+pub mod xonsh;
 pub mod zsh;
 
+/// Environment variable naming the shell, when detection picks the wrong one.
+pub const SHELL_OVERRIDE_VAR: &str = "JVC_SHELL";
+
+/// Shell running jvc: the one named by `JVC_SHELL`, otherwise the closest shell in the process
+/// tree.
+pub fn detect_shell() -> Result<Box<dyn Shell>> {
+    if let Some(name) = env::var_os(SHELL_OVERRIDE_VAR) {
+        let name = name.to_string_lossy();
+        return name
+            .parse()
+            .map_err(|e| anyhow!("Invalid {} {:?}: {}", SHELL_OVERRIDE_VAR, name, e));
+    }
+    detect_process_shell().ok_or_else(|| {
+        anyhow!(
+            "Cannot detect your shell. Please provide your shell with --shell or {}.",
+            SHELL_OVERRIDE_VAR
+        )
+    })
+}
+
 #[cfg(windows)]
-pub fn detect_shell() -> Option<Box<dyn Shell>> {
+fn detect_process_shell() -> Option<Box<dyn Shell>> {
     self::detect::windows::detect_shell()
 }
 
 #[cfg(unix)]
-pub fn detect_shell() -> Option<Box<dyn Shell>> {
+fn detect_process_shell() -> Option<Box<dyn Shell>> {
     self::detect::unix::detect_shell()
 }
+
+/// Pid of the interactive shell running jvc, used to know when its shell link can be removed.
+#[cfg(windows)]
+pub fn shell_pid() -> Option<u32> {
+    self::detect::windows::shell_pid()
+}
+
+#[cfg(unix)]
+pub fn shell_pid() -> Option<u32> {
+    self::detect::unix::shell_pid()
+}
+
+/// Pids from `pids` that are still running.
+#[cfg(windows)]
+pub fn alive_pids(pids: &[u32]) -> HashSet<u32> {
+    self::detect::windows::alive_pids(pids)
+}
+
+#[cfg(unix)]
+pub fn alive_pids(pids: &[u32]) -> HashSet<u32> {
+    self::detect::unix::alive_pids(pids)
+}
@@ -0,0 +1,53 @@
+use super::{quote, shell::Shell};
+use async_trait::async_trait;
+use std::{ffi::OsStr, path::Path};
+
+#[derive(Debug)]
+pub struct Nushell;
+
+#[async_trait]
+impl Shell for Nushell {
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        None
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        // PATH is still a string when the environment is not converted yet
+        format!(
+            "$env.PATH = ($env.PATH | split row (char esep) | prepend {})",
+            self.quote(path.as_os_str())
+        )
+    }
+
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("$env.{} = {}", name, self.quote(value))
+    }
+
+    fn quote(&self, value: &OsStr) -> String {
+        quote::nushell(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"$env.config.hooks.env_change.PWD = (
+    $env.config.hooks.env_change.PWD? | default [] | append {|before, after| ^jvc use --quiet }
+)
+^jvc use --quiet"#
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Nushell;
+    use crate::shell::shell::tests::render_env;
+
+    #[tokio::test]
+    async fn env_script_should_match_golden_file() {
+        assert_eq!(
+            render_env(&Nushell).await,
+            include_str!("fixtures/nushell.txt")
+        );
+    }
+}
@@ -1,24 +1,39 @@
-use super::shell::Shell;
+use super::{quote, shell::Shell};
 use async_trait::async_trait;
+use std::{ffi::OsStr, path::Path};
+
 #[derive(Debug)]
 pub struct PowerShell;
 
 #[async_trait]
 impl Shell for PowerShell {
-    async fn into_clap_shell(&self) -> structopt::clap::Shell {
-        structopt::clap::Shell::PowerShell
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        Some(structopt::clap::Shell::PowerShell)
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        format!(
+            "$env:PATH = {} + [IO.Path]::PathSeparator + $env:PATH",
+            self.quote(path.as_os_str())
+        )
+    }
+
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("$env:{} = {}", name, self.quote(value))
     }
 
-    async fn export_path(&self, path: &std::path::PathBuf) -> String {
-        let current_path = std::env::var_os("PATH").expect("Can't read PATH env var");
-        let mut split_paths: Vec<_> = std::env::split_paths(&current_path).collect();
-        split_paths.insert(0, path.to_path_buf());
-        let new_path = std::env::join_paths(split_paths).expect("Can't join paths");
-        self.set_env_var("PATH", new_path.to_str().expect("Can't read PATH"))
-            .await
+    fn quote(&self, value: &OsStr) -> String {
+        quote::powershell(value)
     }
 
-    async fn set_env_var(&self, name: &str, value: &str) -> String {
-        format!(r#"$env:{} = "{}""#, name, value)
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"function global:Set-JvcOnLoad { & jvc use --quiet }
+function global:Set-LocationWithJvc { param($path); if ($null -eq $path) { Set-Location } else { Set-Location $path }; Set-JvcOnLoad }
+Set-Alias -Scope global cd_with_jvc Set-LocationWithJvc
+Set-Alias -Option AllScope -Scope global cd Set-LocationWithJvc
+Set-JvcOnLoad"#
+                .to_owned(),
+        )
     }
 }
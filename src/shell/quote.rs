@@ -0,0 +1,215 @@
+//! Quoting of values emitted in shell scripts, so they are read back exactly as given and never
+//! expanded. Shells that cannot represent arbitrary bytes get a lossy copy with a warning.
+
+use log::warn;
+use std::{borrow::Cow, ffi::OsStr};
+
+/// bash and zsh: single quotes, where only `'` needs care. Values that are not valid UTF-8
+/// use `$'...'` with hex escapes, so every byte is kept.
+pub fn posix(value: &OsStr) -> String {
+    match value.to_str() {
+        Some(value) => format!("'{}'", value.replace('\'', r"'\''")),
+        None => {
+            let mut quoted = String::from("$'");
+            for byte in bytes(value).iter() {
+                match byte {
+                    b'\\' | b'\'' => quoted.push_str(&format!("\\{}", *byte as char)),
+                    0x20..=0x7e => quoted.push(*byte as char),
+                    _ => quoted.push_str(&format!("\\x{:02x}", byte)),
+                }
+            }
+            quoted.push('\'');
+            quoted
+        }
+    }
+}
+
+/// fish: single quotes escape only `\` and `'`. Bytes that are not valid UTF-8 are written
+/// unquoted as `\XHH` next to the quoted parts.
+pub fn fish(value: &OsStr) -> String {
+    let bytes = bytes(value);
+    let mut quoted = String::new();
+    for chunk in bytes.utf8_chunks() {
+        if !chunk.valid().is_empty() {
+            quoted.push('\'');
+            quoted.push_str(&chunk.valid().replace('\\', r"\\").replace('\'', r"\'"));
+            quoted.push('\'');
+        }
+        for byte in chunk.invalid() {
+            quoted.push_str(&format!("\\X{:02X}", byte));
+        }
+    }
+    if quoted.is_empty() {
+        quoted.push_str("''");
+    }
+    quoted
+}
+
+/// PowerShell: single quotes doubled, including the typographic ones it also accepts.
+pub fn powershell(value: &OsStr) -> String {
+    let mut quoted = String::from("'");
+    for c in lossy(value, "PowerShell").chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// cmd: no quotes, every character with a special meaning is escaped with `^`.
+pub fn cmd(value: &OsStr) -> String {
+    let mut quoted = String::new();
+    for c in lossy(value, "cmd").chars() {
+        if matches!(c, '^' | '&' | '|' | '<' | '>' | '(' | ')' | '%' | '!' | '"') {
+            quoted.push('^');
+        }
+        quoted.push(c);
+    }
+    quoted
+}
+
+/// Nushell: double quotes without interpolation, with backslash escapes.
+pub fn nushell(value: &OsStr) -> String {
+    format!("\"{}\"", escape_backslash(&lossy(value, "Nushell"), '"'))
+}
+
+/// Elvish: single quotes, where `'` is doubled and nothing else is special.
+pub fn elvish(value: &OsStr) -> String {
+    format!("'{}'", lossy(value, "Elvish").replace('\'', "''"))
+}
+
+/// Xonsh: a python string literal.
+pub fn xonsh(value: &OsStr) -> String {
+    format!("'{}'", escape_backslash(&lossy(value, "Xonsh"), '\''))
+}
+
+fn escape_backslash(value: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\t' => escaped.push_str(r"\t"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn lossy<'a>(value: &'a OsStr, shell: &str) -> Cow<'a, str> {
+    let lossy = value.to_string_lossy();
+    if let Cow::Owned(_) = lossy {
+        warn!(
+            "{} cannot represent {:?}, invalid characters are replaced",
+            shell, value
+        );
+    }
+    lossy
+}
+
+#[cfg(unix)]
+fn bytes(value: &OsStr) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(value.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn bytes(value: &OsStr) -> Cow<'_, [u8]> {
+    match value.to_string_lossy() {
+        Cow::Borrowed(value) => Cow::Borrowed(value.as_bytes()),
+        Cow::Owned(value) => Cow::Owned(value.into_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cmd, elvish, fish, nushell, posix, powershell, xonsh};
+    use std::ffi::OsStr;
+
+    #[test]
+    fn special_characters_should_be_quoted() {
+        let value = OsStr::new(r#"C:\it's "$HOME" 100% `x` & more"#);
+
+        assert_eq!(posix(value), r#"'C:\it'\''s "$HOME" 100% `x` & more'"#);
+        assert_eq!(fish(value), r#"'C:\\it\'s "$HOME" 100% `x` & more'"#);
+        assert_eq!(powershell(value), r#"'C:\it''s "$HOME" 100% `x` & more'"#);
+        assert_eq!(cmd(value), r#"C:\it's ^"$HOME^" 100^% `x` ^& more"#);
+        assert_eq!(nushell(value), r#""C:\\it's \"$HOME\" 100% `x` & more""#);
+        assert_eq!(elvish(value), r#"'C:\it''s "$HOME" 100% `x` & more'"#);
+        assert_eq!(xonsh(value), r#"'C:\\it\'s "$HOME" 100% `x` & more'"#);
+    }
+
+    #[test]
+    fn powershell_should_double_typographic_quotes() {
+        assert_eq!(powershell(OsStr::new("a\u{2019}b")), "'a\u{2019}\u{2019}b'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn invalid_utf8_should_keep_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+        let value = OsStr::from_bytes(b"/tmp/\xff'x");
+
+        assert_eq!(posix(value), r"$'/tmp/\xff\'x'");
+        assert_eq!(fish(value), r"'/tmp/'\XFF'\'x'");
+    }
+
+    /// Round trip values through a real bash, which reads them back from the emitted script.
+    #[cfg(unix)]
+    mod bash {
+        use crate::shell::{bash::Bash, shell::Shell};
+        use proptest::prelude::*;
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path, process::Command};
+
+        fn run_bash(script: &str) -> Vec<u8> {
+            let output = Command::new("bash")
+                .arg("-c")
+                .arg(script)
+                .env("PATH", "/usr/bin:/bin")
+                .output()
+                .expect("Cannot run bash");
+            assert!(output.status.success(), "bash failed for {:?}", script);
+            output.stdout
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn env_var_should_round_trip(value in proptest::collection::vec(1u8..=255u8, 0..40)) {
+                let value = OsStr::from_bytes(&value);
+                let script = format!(
+                    "{}\nprintf '%s' \"$JVC_TEST\"",
+                    block_on(Bash.set_env_var("JVC_TEST", value))
+                );
+                prop_assert_eq!(run_bash(&script), value.as_bytes());
+            }
+
+            #[test]
+            fn path_should_round_trip(value in proptest::collection::vec(1u8..=255u8, 1..40)) {
+                let path = Path::new(OsStr::from_bytes(&value));
+                let script = format!(
+                    "{}\nprintf '%s' \"$PATH\"",
+                    block_on(Bash.export_path(path))
+                );
+                let mut expected = value.clone();
+                expected.extend_from_slice(b":/usr/bin:/bin");
+                prop_assert_eq!(run_bash(&script), expected);
+            }
+        }
+
+        fn block_on<F: std::future::Future>(future: F) -> F::Output {
+            tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+                .block_on(future)
+        }
+    }
+}
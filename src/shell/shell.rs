@@ -1,20 +1,36 @@
 use async_trait::async_trait;
-use std::{path::PathBuf, str::FromStr};
+use std::{ffi::OsStr, path::Path, str::FromStr};
 
-use super::{bash::Bash, powershell::PowerShell, windows_cmd::WindowsCMD, zsh::Zsh};
+use super::{
+    bash::Bash, elvish::Elvish, fish::Fish, nushell::Nushell, powershell::PowerShell,
+    windows_cmd::WindowsCMD, xonsh::Xonsh, zsh::Zsh,
+};
 
 #[async_trait]
 pub trait Shell: std::fmt::Debug + Send + Sync {
-    async fn into_clap_shell(&self) -> structopt::clap::Shell;
-    async fn export_path(&self, path: &PathBuf) -> String;
-    async fn set_env_var(&self, name: &str, value: &str) -> String;
+    /// Shell for the completion script generated by clap, if clap supports it
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell>;
+    async fn export_path(&self, path: &Path) -> String;
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String;
+    /// Quote `value` so the shell reads it back unchanged
+    fn quote(&self, value: &OsStr) -> String;
+    /// Script that runs `jvc use` whenever the current directory changes
+    async fn use_on_cd(&self) -> Option<String>;
 }
 
 #[cfg(windows)]
 pub const AVAILABLE_SHELLS: &[&str; 4] = &["cmd", "powershell", "bash", "zsh"];
 
 #[cfg(unix)]
-pub const AVAILABLE_SHELLS: &[&str; 3] = &["bash", "zsh", "powershell"];
+pub const AVAILABLE_SHELLS: &[&str; 7] = &[
+    "bash",
+    "zsh",
+    "fish",
+    "nushell",
+    "elvish",
+    "xonsh",
+    "powershell",
+];
 
 impl FromStr for Box<dyn Shell> {
     type Err = String;
@@ -24,6 +40,10 @@ impl FromStr for Box<dyn Shell> {
             "cmd" => Ok(Box::from(WindowsCMD)),
             "zsh" => Ok(Box::from(Zsh)),
             "bash" => Ok(Box::from(Bash)),
+            "fish" => Ok(Box::from(Fish)),
+            "nu" | "nushell" => Ok(Box::from(Nushell)),
+            "elvish" => Ok(Box::from(Elvish)),
+            "xonsh" => Ok(Box::from(Xonsh)),
             "powershell" => Ok(Box::from(PowerShell)),
             shell_type => Err(format!("Cannot identify shell type: {:?}", shell_type)),
         }
@@ -31,8 +51,27 @@ impl FromStr for Box<dyn Shell> {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::{Shell, AVAILABLE_SHELLS};
+    use std::{ffi::OsStr, path::PathBuf};
+
+    /// Script printed by `jvc env --use-on-cd` for a shell link in `/tmp`, used by golden files.
+    pub async fn render_env(shell: &dyn Shell) -> String {
+        let shell_path = "/tmp/jvc_shell_1_2";
+        let bin_path = PathBuf::from(shell_path).join("installation").join("bin");
+        let lines = [
+            shell.export_path(&bin_path).await,
+            shell
+                .set_env_var("JVC_SHELL_PATH", OsStr::new(shell_path))
+                .await,
+            shell
+                .set_env_var("JAVA_HOME", OsStr::new("/tmp/jvc_shell_1_2/installation"))
+                .await,
+            shell.use_on_cd().await.unwrap_or_default(),
+        ];
+        format!("{}\n", lines.join("\n"))
+    }
+
     #[test]
     fn available_shells_should_be_parsable_from_string() {
         for shell_name in AVAILABLE_SHELLS {
@@ -40,4 +79,23 @@ mod tests {
             assert!(shell.is_ok())
         }
     }
+
+    #[tokio::test]
+    async fn only_shells_supported_by_clap_should_have_completions() {
+        let mut completed = vec![];
+        for shell_name in AVAILABLE_SHELLS {
+            let shell = shell_name.parse::<Box<dyn Shell>>().unwrap();
+            if shell.into_clap_shell().await.is_some() {
+                completed.push(*shell_name);
+            }
+        }
+
+        #[cfg(unix)]
+        assert_eq!(
+            completed,
+            vec!["bash", "zsh", "fish", "elvish", "powershell"]
+        );
+        #[cfg(windows)]
+        assert_eq!(completed, vec!["powershell", "bash", "zsh"]);
+    }
 }
@@ -0,0 +1,47 @@
+use super::{quote, shell::Shell};
+use async_trait::async_trait;
+use std::{ffi::OsStr, path::Path};
+
+#[derive(Debug)]
+pub struct Xonsh;
+
+#[async_trait]
+impl Shell for Xonsh {
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        None
+    }
+
+    async fn export_path(&self, path: &Path) -> String {
+        format!("$PATH.insert(0, {})", self.quote(path.as_os_str()))
+    }
+
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("${} = {}", name, self.quote(value))
+    }
+
+    fn quote(&self, value: &OsStr) -> String {
+        quote::xonsh(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"@events.on_chdir
+def __jvc_use_on_cd(olddir, newdir, **kwargs):
+    ![jvc use --quiet]
+
+![jvc use --quiet]"#
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Xonsh;
+    use crate::shell::shell::tests::render_env;
+
+    #[tokio::test]
+    async fn env_script_should_match_golden_file() {
+        assert_eq!(render_env(&Xonsh).await, include_str!("fixtures/xonsh.txt"));
+    }
+}
@@ -1,26 +1,38 @@
-use super::shell::Shell;
+use super::{quote, shell::Shell};
 use async_trait::async_trait;
-use log::warn;
+use std::{ffi::OsStr, path::Path};
 
 #[derive(Debug)]
 pub struct Zsh;
 
 #[async_trait]
 impl Shell for Zsh {
-    async fn into_clap_shell(&self) -> structopt::clap::Shell {
-        structopt::clap::Shell::Zsh
+    async fn into_clap_shell(&self) -> Option<structopt::clap::Shell> {
+        Some(structopt::clap::Shell::Zsh)
     }
 
-    async fn export_path(&self, path: &std::path::PathBuf) -> String {
-        if let Some(new_path) = path.to_str() {
-            format!("export PATH={:?}:$PATH", new_path)
-        } else {
-            warn!("Cannot construct path for Path variable");
-            "".to_owned()
-        }
+    async fn export_path(&self, path: &Path) -> String {
+        format!("export PATH={}:\"$PATH\"", self.quote(path.as_os_str()))
     }
 
-    async fn set_env_var(&self, name: &str, value: &str) -> String {
-        format!("export {}={:?}", name, value)
+    async fn set_env_var(&self, name: &str, value: &OsStr) -> String {
+        format!("export {}={}", name, self.quote(value))
+    }
+
+    fn quote(&self, value: &OsStr) -> String {
+        quote::posix(value)
+    }
+
+    async fn use_on_cd(&self) -> Option<String> {
+        Some(
+            r#"autoload -U add-zsh-hook
+_jvc_autoload_hook() {
+    jvc use --quiet
+}
+
+add-zsh-hook chpwd _jvc_autoload_hook \
+    && _jvc_autoload_hook"#
+                .to_owned(),
+        )
     }
 }
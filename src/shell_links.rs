@@ -0,0 +1,155 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::{
+    collections::HashSet,
+    fs::{self, read_dir, read_link, read_to_string, remove_file},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use symlink::remove_symlink_dir;
+
+use crate::shell::alive_pids;
+
+/// Directory of the base dir holding one record per tracked shell link.
+pub const SHELLS_DIR: &str = "shells";
+
+/// Prefix of the links created by `jvc env` in the temp dir.
+pub const SHELL_LINK_PREFIX: &str = "jvc_shell_";
+
+/// Shell link created by `jvc env`, recorded as `<shells dir>/<link name>` holding the pid of
+/// the shell that evaluated it and the link path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellLink {
+    pub pid: u32,
+    pub path: PathBuf,
+    record: PathBuf,
+}
+
+/// Remember that `link` belongs to the shell with `pid`.
+pub fn register(shells_dir: &Path, pid: u32, link: &Path) -> Result<()> {
+    let name = link
+        .file_name()
+        .ok_or_else(|| anyhow!("Cannot track shell link {:?}", link))?;
+    let record = shells_dir.join(name);
+    let tmp = record.with_extension("tmp");
+    fs::write(&tmp, format!("{}\n{}\n", pid, link.to_string_lossy()))?;
+    fs::rename(&tmp, &record)?;
+    debug!("Track shell link {:?} of process {}", link, pid);
+    Ok(())
+}
+
+/// All recorded shell links. Unreadable records are skipped.
+pub fn tracked(shells_dir: &Path) -> Result<Vec<ShellLink>> {
+    if !shells_dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut links = vec![];
+    for entry in read_dir(shells_dir)? {
+        let record = entry?.path();
+        if record.extension().is_some_and(|ext| ext == "tmp") {
+            continue;
+        }
+        match parse_record(&record) {
+            Some((pid, path)) => links.push(ShellLink { pid, path, record }),
+            None => debug!("Ignore shell link record {:?}", record),
+        }
+    }
+    links.sort_by_key(|link| link.pid);
+    Ok(links)
+}
+
+/// Recorded links whose shell is not running anymore.
+pub fn stale_links(shells_dir: &Path) -> Result<Vec<ShellLink>> {
+    let links = tracked(shells_dir)?;
+    let pids: Vec<u32> = links.iter().map(|link| link.pid).collect();
+    let alive = alive_pids(&pids);
+    Ok(stale(links, &alive))
+}
+
+/// Links whose shell is not in `alive`.
+fn stale(links: Vec<ShellLink>, alive: &HashSet<u32>) -> Vec<ShellLink> {
+    links
+        .into_iter()
+        .filter(|link| !alive.contains(&link.pid))
+        .collect()
+}
+
+/// Remove the link and its record.
+pub fn remove(link: &ShellLink) -> Result<()> {
+    let is_shell_link = link
+        .path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with(SHELL_LINK_PREFIX));
+    // the path comes from a file on disk, never remove anything but a jvc link
+    if is_shell_link && read_link(&link.path).is_ok() {
+        remove_symlink_dir(&link.path)?;
+    }
+    match remove_file(&link.record) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn parse_record(record: &Path) -> Option<(u32, PathBuf)> {
+    let content = read_to_string(record).ok()?;
+    let mut lines = content.lines();
+    let pid = lines.next()?.trim().parse().ok()?;
+    let path = lines
+        .next()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())?;
+    Some((pid, PathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register, remove, stale, tracked};
+    use std::{collections::HashSet, fs::create_dir_all};
+    use symlink::symlink_dir;
+    use tempdir::TempDir;
+
+    #[test]
+    fn stale_links_should_be_removed_with_their_record() {
+        let dir = TempDir::new("jvc-shells").unwrap();
+        let shells_dir = dir.path().join("shells");
+        create_dir_all(&shells_dir).unwrap();
+        let version = dir.path().join("17.0.2-lts-adoptium");
+        create_dir_all(&version).unwrap();
+        let alive_link = dir.path().join("jvc_shell_10_1");
+        let dead_link = dir.path().join("jvc_shell_20_2");
+        symlink_dir(&version, &alive_link).unwrap();
+        symlink_dir(&version, &dead_link).unwrap();
+        register(&shells_dir, 100, &alive_link).unwrap();
+        register(&shells_dir, 200, &dead_link).unwrap();
+
+        let alive: HashSet<u32> = vec![100].into_iter().collect();
+        let stale_links = stale(tracked(&shells_dir).unwrap(), &alive);
+        assert_eq!(stale_links.len(), 1);
+        assert_eq!(stale_links[0].pid, 200);
+        remove(&stale_links[0]).unwrap();
+
+        assert!(!dead_link.exists());
+        assert!(alive_link.exists());
+        assert!(version.exists());
+        let remaining = tracked(&shells_dir).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].path, alive_link);
+    }
+
+    #[test]
+    fn remove_should_only_delete_jvc_links() {
+        let dir = TempDir::new("jvc-shells").unwrap();
+        let shells_dir = dir.path().join("shells");
+        create_dir_all(&shells_dir).unwrap();
+        let version = dir.path().join("17.0.2-lts-adoptium");
+        let other_link = dir.path().join("important");
+        create_dir_all(&version).unwrap();
+        symlink_dir(&version, &other_link).unwrap();
+        register(&shells_dir, 200, &other_link).unwrap();
+
+        remove(&tracked(&shells_dir).unwrap()[0]).unwrap();
+
+        assert!(other_link.exists());
+        assert!(tracked(&shells_dir).unwrap().is_empty());
+    }
+}
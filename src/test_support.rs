@@ -0,0 +1,37 @@
+//! Fixtures shared by the tests of providers, installs and commands.
+
+use flate2::{write::GzEncoder, Compression};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// Path of the java binary in a JDK at `root`.
+pub fn fixture_java(root: &str) -> String {
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    format!("{}/bin/{}", root, java)
+}
+
+/// Writes a small jdk-like `.tar.gz` archive and returns its path.
+pub fn create_fixture_archive(dir: &Path) -> PathBuf {
+    let archive_path = dir.join("fixture-jdk.tar.gz");
+    let encoder = GzEncoder::new(File::create(&archive_path).unwrap(), Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let content = b"JAVA_VERSION=\"11.0.12\"\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "fixture-jdk/release", &content[..])
+        .unwrap();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(0);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, fixture_java("fixture-jdk"), &[][..])
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+    archive_path
+}
@@ -1,45 +1,344 @@
 use anyhow::anyhow;
 use anyhow::Result;
-use serde::Deserialize;
-use std::{fmt::Display, str::FromStr};
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
 use crate::provider::Provider;
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// A java version following the `$FEATURE.$INTERIM.$UPDATE.$PATCH[-$PRE][+$BUILD]` scheme.
+///
+/// Legacy versions like `1.8.0_292-b10` are read as `8.0.292+10`. Components that
+/// were not specified are `None`, which allows a version like `11.0` to be used as a prefix.
+#[derive(Debug, Clone, Default)]
+pub struct JavaVersion {
+    pub feature: u32,
+    pub interim: Option<u32>,
+    pub update: Option<u32>,
+    pub patch: Option<u32>,
+    pub pre: Option<String>,
+    pub build: Option<u32>,
+    pub zulu_version: Option<Vec<u32>>,
+}
+
+impl JavaVersion {
+    pub fn from_feature(feature: u32) -> Self {
+        Self {
+            feature,
+            ..Self::default()
+        }
+    }
+
+    /// Create a version from numeric components as returned by Azul: `[11, 0, 12, 7]`.
+    /// The fourth component is the build number.
+    pub fn from_components(components: &[u32]) -> Result<Self> {
+        let feature = *components
+            .first()
+            .ok_or_else(|| anyhow!("Cannot create java version without components"))?;
+        Ok(Self {
+            feature,
+            interim: components.get(1).copied(),
+            update: components.get(2).copied(),
+            patch: None,
+            pre: None,
+            build: components.get(3).copied(),
+            zulu_version: None,
+        })
+    }
+
+    pub fn with_zulu_version(mut self, zulu_version: Vec<u32>) -> Self {
+        self.zulu_version = Some(zulu_version);
+        self
+    }
+
+    pub fn is_feature_only(&self) -> bool {
+        self.interim.is_none() && self.pre.is_none() && self.build.is_none()
+    }
+
+    /// Every component specified in `self` is equal in `other`.
+    pub fn is_prefix_of(&self, other: &JavaVersion) -> bool {
+        fn same(prefix: Option<u32>, value: Option<u32>) -> bool {
+            prefix.is_none_or(|p| p == value.unwrap_or(0))
+        }
+
+        self.feature == other.feature
+            && same(self.interim, other.interim)
+            && same(self.update, other.update)
+            && same(self.patch, other.patch)
+            && self
+                .pre
+                .as_ref()
+                .is_none_or(|p| Some(p) == other.pre.as_ref())
+            && self.build.is_none_or(|b| Some(b) == other.build)
+    }
+
+    fn sort_key(&self) -> (u32, u32, u32, u32, bool, u32) {
+        (
+            self.feature,
+            self.interim.unwrap_or(0),
+            self.update.unwrap_or(0),
+            self.patch.unwrap_or(0),
+            self.pre.is_none(),
+            self.build.unwrap_or(0),
+        )
+    }
+}
+
+impl FromStr for JavaVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value.strip_prefix("jdk-").unwrap_or(value);
+        if value.starts_with("1.") {
+            parse_legacy_version(value)
+        } else {
+            parse_version(value)
+        }
+        .ok_or_else(|| anyhow!("Cannot parse java version {}", value))
+    }
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse::<u32>().ok()
+}
+
+/// Parse the leading digits of build numbers like `7`, `7.1` or `b10`.
+fn parse_build(value: &str) -> Option<u32> {
+    let digits: String = value
+        .trim_start_matches('b')
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    parse_number(&digits)
+}
+
+fn parse_version(value: &str) -> Option<JavaVersion> {
+    let (version, build) = match value.split_once('+') {
+        Some((version, build)) => (version, Some(parse_build(build)?)),
+        None => (value, None),
+    };
+    let (numbers, pre) = match version.split_once('-') {
+        Some((numbers, pre)) if !pre.is_empty() => (numbers, Some(pre.to_owned())),
+        Some(_) => return None,
+        None => (version, None),
+    };
+    let components = numbers
+        .split('.')
+        .map(parse_number)
+        .collect::<Option<Vec<u32>>>()?;
+
+    Some(JavaVersion {
+        feature: *components.first()?,
+        interim: components.get(1).copied(),
+        update: components.get(2).copied(),
+        patch: components.get(3).copied(),
+        pre,
+        build,
+        zulu_version: None,
+    })
+}
+
+/// Parse versions like `1.8`, `1.8.0_292` or `1.8.0_292-b10`.
+fn parse_legacy_version(value: &str) -> Option<JavaVersion> {
+    let (version, build) = match value.split_once('-') {
+        Some((version, build)) => (version, Some(parse_build(build)?)),
+        None => (value, None),
+    };
+    let (numbers, update) = match version.split_once('_') {
+        Some((numbers, update)) => (numbers, Some(parse_number(update)?)),
+        None => (version, None),
+    };
+    let components = numbers
+        .split('.')
+        .map(parse_number)
+        .collect::<Option<Vec<u32>>>()?;
+
+    Some(JavaVersion {
+        feature: *components.get(1)?,
+        interim: components.get(2).copied(),
+        update,
+        patch: None,
+        pre: None,
+        build,
+        zulu_version: None,
+    })
+}
+
+impl Display for JavaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.feature)?;
+        for component in [self.interim, self.update, self.patch].iter().flatten() {
+            write!(f, ".{}", component)?;
+        }
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for JavaVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for JavaVersion {}
+
+impl PartialOrd for JavaVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JavaVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+/// LTS releases are 8, 11, 17 and every fourth feature release since.
+pub fn is_lts_feature(feature: u32) -> bool {
+    feature == 8 || feature == 11 || (feature >= 17 && (feature - 17).is_multiple_of(4))
+}
+
+/// A version requirement like `11`, `11.0.12+7`, `>=17` or `<11`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionReq {
+    Matching(JavaVersion),
+    GreaterOrEqual(JavaVersion),
+    Greater(JavaVersion),
+    LessOrEqual(JavaVersion),
+    Less(JavaVersion),
+}
+
+impl VersionReq {
+    pub fn matches(&self, version: &JavaVersion) -> bool {
+        match self {
+            VersionReq::Matching(req) => req.is_prefix_of(version),
+            VersionReq::GreaterOrEqual(req) => version >= req,
+            VersionReq::Greater(req) => version > req && !req.is_prefix_of(version),
+            VersionReq::LessOrEqual(req) => version < req || req.is_prefix_of(version),
+            VersionReq::Less(req) => version < req,
+        }
+    }
+
+    /// Whether some version of the given feature release can satisfy this requirement.
+    pub fn allows_feature(&self, feature: u32) -> bool {
+        match self {
+            VersionReq::Matching(req) => feature == req.feature,
+            VersionReq::GreaterOrEqual(req) => feature >= req.feature,
+            VersionReq::Greater(req) if req.is_feature_only() => feature > req.feature,
+            VersionReq::Greater(req) => feature >= req.feature,
+            VersionReq::LessOrEqual(req) => feature <= req.feature,
+            VersionReq::Less(req) if req.is_feature_only() => feature < req.feature,
+            VersionReq::Less(req) => feature <= req.feature,
+        }
+    }
+
+    pub fn version(&self) -> &JavaVersion {
+        match self {
+            VersionReq::Matching(req)
+            | VersionReq::GreaterOrEqual(req)
+            | VersionReq::Greater(req)
+            | VersionReq::LessOrEqual(req)
+            | VersionReq::Less(req) => req,
+        }
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Some(version) = value.strip_prefix(">=") {
+            Ok(VersionReq::GreaterOrEqual(version.parse()?))
+        } else if let Some(version) = value.strip_prefix("<=") {
+            Ok(VersionReq::LessOrEqual(version.parse()?))
+        } else if let Some(version) = value.strip_prefix('>') {
+            Ok(VersionReq::Greater(version.parse()?))
+        } else if let Some(version) = value.strip_prefix('<') {
+            Ok(VersionReq::Less(version.parse()?))
+        } else if let Some(version) = value.strip_prefix('=') {
+            Ok(VersionReq::Matching(version.parse()?))
+        } else {
+            Ok(VersionReq::Matching(value.parse()?))
+        }
+    }
+}
+
+impl Display for VersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionReq::Matching(req) => write!(f, "{}", req),
+            VersionReq::GreaterOrEqual(req) => write!(f, ">={}", req),
+            VersionReq::Greater(req) => write!(f, ">{}", req),
+            VersionReq::LessOrEqual(req) => write!(f, "<={}", req),
+            VersionReq::Less(req) => write!(f, "<{}", req),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Version {
-    pub value: String,
+    pub value: JavaVersion,
     pub lts: bool,
     pub provider: Provider,
-    pub semver_value: Option<String>,
+    /// Distribution installed through a provider serving several of them, like `corretto` for disco
+    pub distribution: Option<String>,
 }
 
 impl Version {
     pub fn new(value: u32, is_lts: bool, provider: Provider) -> Self {
+        Self::from_java_version(JavaVersion::from_feature(value), is_lts, provider)
+    }
+
+    pub fn from_java_version(value: JavaVersion, is_lts: bool, provider: Provider) -> Self {
         Self {
-            value: value.to_string(),
+            value,
             lts: is_lts,
-            semver_value: None,
-            provider: provider,
+            provider,
+            distribution: None,
         }
     }
 
+    pub fn with_distribution(mut self, distribution: Option<String>) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
     pub fn new_from_disk(name: &str) -> Self {
-        let values: Vec<&str> = name.split('-').collect();
-
-        if values.len() == 2 {
-            Self {
-                value: values[0].to_string(),
-                lts: false,
-                provider: Provider::from_str(values[1]).expect("Cannot read version from disk"),
-                semver_value: None,
-            }
-        } else {
-            Self {
-                value: values[0].to_string(),
-                lts: true,
-                provider: Provider::from_str(values[2]).expect("Cannot read version from disk"),
-                semver_value: None,
-            }
-        }
+        Self::parse_disk_name(name).expect("Cannot read version from disk")
+    }
+
+    /// Parse installation directory names like `17-lts-azul`, `11.0.12+7-lts-adoptopenjdk`
+    /// or `17.0.2+8-lts-disco.corretto`.
+    pub fn parse_disk_name(name: &str) -> Result<Self> {
+        let (rest, source) = name
+            .rsplit_once('-')
+            .ok_or_else(|| anyhow!("Cannot read version from disk: {}", name))?;
+        let (provider, distribution) = match source.split_once('.') {
+            Some((provider, distribution)) => (provider, Some(distribution.to_owned())),
+            None => (source, None),
+        };
+        let (version, lts) = match rest.strip_suffix("-lts") {
+            Some(version) => (version, true),
+            None => (rest, false),
+        };
+
+        Ok(Self {
+            value: version.parse()?,
+            lts,
+            provider: Provider::from_str(provider)?,
+            distribution,
+        })
     }
 
     pub fn is_lts(&self) -> bool {
@@ -47,18 +346,38 @@ impl Version {
     }
 
     pub fn as_disk_version(&self) -> String {
-        let version_name: &str = self.value.as_ref();
         if self.is_lts() {
             format!("{}-lts", self.value)
         } else {
-            format!("{}", version_name.to_owned())
+            self.value.to_string()
+        }
+    }
+
+    /// Provider and distribution as recorded in the installation directory name.
+    pub fn source_name(&self) -> String {
+        match &self.distribution {
+            Some(distribution) => format!("{}.{}", self.provider.as_str(), distribution),
+            None => self.provider.as_str(),
         }
     }
 
-    pub fn to_version_number(&self) -> Result<u8> {
-        self.value
-            .parse::<u8>()
-            .map_err(|_e| anyhow!("Cannot parse string to major version"))
+    /// Installation directory name like `17.0.2+8-lts-disco.corretto`.
+    pub fn as_disk_name(&self) -> String {
+        format!("{}-{}", self.as_disk_version(), self.source_name())
+    }
+}
+
+/// Serialized as the feature version, the full version, the LTS flag and the source,
+/// which is the schema of `jvc list --json`.
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Version", 5)?;
+        state.serialize_field("version", &self.value.feature)?;
+        state.serialize_field("full_version", &self.value.to_string())?;
+        state.serialize_field("lts", &self.lts)?;
+        state.serialize_field("provider", &self.provider)?;
+        state.serialize_field("distribution", &self.distribution)?;
+        state.end()
     }
 }
 
@@ -72,7 +391,7 @@ impl Display for Version {
 mod tests {
     use crate::provider::Provider;
 
-    use super::Version;
+    use super::{is_lts_feature, JavaVersion, Version, VersionReq};
 
     #[test]
     fn version_should_display_main_version() {
@@ -81,13 +400,6 @@ mod tests {
         assert_eq!(format!("{}", 10), string_result);
     }
 
-    #[test]
-    fn should_extract_major_version_as_number() {
-        let version = Version::new_from_disk("8-lts-azul").to_version_number();
-        assert!(version.is_ok());
-        assert_eq!(8, version.unwrap());
-    }
-
     #[test]
     fn should_create_version_from_disk() {
         let version = Version::new_from_disk("8-lts-azul");
@@ -101,4 +413,119 @@ mod tests {
         assert_eq!(version.is_lts(), false);
         assert_eq!(version.provider, Provider::Azul);
     }
+
+    #[test]
+    fn should_create_full_version_from_disk() {
+        let version = Version::new_from_disk("17-ea+5-adoptopenjdk");
+        assert!(!version.is_lts());
+        assert_eq!(version.value.pre, Some("ea".to_owned()));
+        assert_eq!(version.value.build, Some(5));
+
+        let version = Version::new_from_disk("11.0.12+7-lts-azul");
+        assert!(version.is_lts());
+        assert_eq!(version.as_disk_version(), "11.0.12+7-lts");
+    }
+
+    #[test]
+    fn should_create_version_with_distribution_from_disk() {
+        let version = Version::new_from_disk("17.0.2+8-lts-disco.corretto");
+        assert!(version.is_lts());
+        assert_eq!(version.provider, Provider::Disco);
+        assert_eq!(version.distribution, Some("corretto".to_owned()));
+        assert_eq!(version.as_disk_name(), "17.0.2+8-lts-disco.corretto");
+
+        let version = Version::new_from_disk("11.0.12+7-azul");
+        assert_eq!(version.distribution, None);
+        assert_eq!(version.as_disk_name(), "11.0.12+7-azul");
+    }
+
+    #[test]
+    fn should_parse_adoptopenjdk_versions() {
+        let version: JavaVersion = "11.0.12+7".parse().unwrap();
+        assert_eq!(version.feature, 11);
+        assert_eq!(version.interim, Some(0));
+        assert_eq!(version.update, Some(12));
+        assert_eq!(version.build, Some(7));
+        assert_eq!(version.to_string(), "11.0.12+7");
+
+        let version: JavaVersion = "jdk-17.0.1+12".parse().unwrap();
+        assert_eq!(version.to_string(), "17.0.1+12");
+    }
+
+    #[test]
+    fn should_parse_legacy_versions() {
+        let version: JavaVersion = "1.8.0_292-b10".parse().unwrap();
+        assert_eq!(version.feature, 8);
+        assert_eq!(version.update, Some(292));
+        assert_eq!(version.build, Some(10));
+        assert_eq!(version, "8.0.292+10".parse().unwrap());
+    }
+
+    #[test]
+    fn should_parse_azul_versions() {
+        let version = JavaVersion::from_components(&[11, 0, 12, 7])
+            .unwrap()
+            .with_zulu_version(vec![11, 50, 19]);
+        assert_eq!(version.to_string(), "11.0.12+7");
+        assert_eq!(version.zulu_version, Some(vec![11, 50, 19]));
+    }
+
+    #[test]
+    fn should_reject_invalid_versions() {
+        assert!("default".parse::<JavaVersion>().is_err());
+        assert!("11.x".parse::<JavaVersion>().is_err());
+        assert!("".parse::<JavaVersion>().is_err());
+    }
+
+    #[test]
+    fn versions_should_be_ordered() {
+        let mut versions: Vec<JavaVersion> =
+            vec!["11.0.20+8", "17-ea+5", "11.0.12+7", "17+35", "8"]
+                .into_iter()
+                .map(|v| v.parse().unwrap())
+                .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            vec!["8", "11.0.12+7", "11.0.20+8", "17-ea+5", "17+35"]
+        );
+    }
+
+    #[test]
+    fn version_requirements_should_match() {
+        let version: JavaVersion = "11.0.12+7".parse().unwrap();
+        let matches = |req: &str| req.parse::<VersionReq>().unwrap().matches(&version);
+
+        assert!(matches("11"));
+        assert!(matches("11.0"));
+        assert!(matches("11.0.12"));
+        assert!(matches("11.0.12+7"));
+        assert!(!matches("11.0.12+8"));
+        assert!(!matches("17"));
+        assert!(matches(">=11"));
+        assert!(!matches(">11"));
+        assert!(matches(">11.0.10"));
+        assert!(matches("<=11"));
+        assert!(!matches("<11"));
+        assert!(matches("<17"));
+    }
+
+    #[test]
+    fn version_requirements_should_allow_features() {
+        let req: VersionReq = ">=17".parse().unwrap();
+        assert!(req.allows_feature(17));
+        assert!(req.allows_feature(21));
+        assert!(!req.allows_feature(11));
+
+        let req: VersionReq = "<17".parse().unwrap();
+        assert!(!req.allows_feature(17));
+        assert!(req.allows_feature(11));
+    }
+
+    #[test]
+    fn is_lts_feature_should_follow_release_cadence() {
+        let lts: Vec<u32> = (8..=25).filter(|f| is_lts_feature(*f)).collect();
+        assert_eq!(lts, vec![8, 11, 17, 21, 25]);
+    }
 }